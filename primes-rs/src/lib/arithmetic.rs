@@ -0,0 +1,413 @@
+use wasm_bindgen::prelude::*;
+
+use crate::factorization::factor_pairs;
+use crate::iif;
+use crate::modular::lcm64;
+use crate::util::is_even;
+
+#[wasm_bindgen]
+pub fn totient(num: u32) -> u32 {
+    match num {
+        0 => 0,
+        n => factor_pairs(n as u64)
+            .into_iter()
+            .map(|(p, e)| (p.pow(e - 1) * (p - 1)) as u32)
+            .product(),
+    }
+}
+
+#[wasm_bindgen(js_name = carmichaelLambda)]
+pub fn carmichael_lambda(num: u32) -> u32 {
+    match num {
+        0 => 0,
+        n => factor_pairs(n as u64)
+            .into_iter()
+            .map(|(p, e)| prime_power_lambda(p.pow(e), p))
+            .try_fold(1, lcm64)
+            .expect("lambda(n) divides totient(n), which fits in a u32") as u32,
+    }
+}
+
+#[wasm_bindgen]
+pub fn mobius(num: u32) -> i8 {
+    match num {
+        0 => 0,
+        n => {
+            let factors = factor_pairs(n as u64);
+            match factors.iter().all(|&(_, e)| e == 1) {
+                true => iif!(is_even(factors.len() as u32) => 1; -1),
+                _ => 0,
+            }
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn liouville(num: u32) -> i8 {
+    match num {
+        0 => 0,
+        n => iif!(is_even(big_omega(n)) => 1; -1),
+    }
+}
+
+/// Amount of distinct prime factors of `num`.
+#[wasm_bindgen]
+pub fn omega(num: u32) -> u32 {
+    factor_pairs(num as u64).len() as u32
+}
+
+/// Amount of prime factors of `num`, counted with multiplicity.
+#[wasm_bindgen(js_name = bigOmega)]
+pub fn big_omega(num: u32) -> u32 {
+    factor_pairs(num as u64).into_iter().map(|(_, e)| e).sum()
+}
+
+/// Mertens function `M(x)`, the sum of `mobius(n)` for `n` in `1..=x`. Values up to `x^(2/3)` come
+/// from a sieve, the larger ones from the identity `sum(M(x / d), d = 1..=x) = 1`, in `O(x^(2/3))`.
+#[wasm_bindgen]
+pub fn mertens(x: u32) -> i32 {
+    let x = x as u64;
+    let limit = ((x as f64).powf(2.0 / 3.0) as u64).clamp(1, x.max(1));
+    let small: Vec<i64> = mobius_table(limit as u32)
+        .into_iter()
+        .scan(0, |sum, mu| {
+            *sum += mu as i64;
+            Some(*sum)
+        })
+        .collect();
+    if x <= limit {
+        return small[x as usize] as i32;
+    }
+    // large[k] holds M(x / k) for every k with x / k > limit.
+    let count = (x / (limit + 1)) as usize;
+    let mut large = vec![0; count + 1];
+    for k in (1..=count).rev() {
+        let v = x / k as u64;
+        let (mut sum, mut d) = (1, 2);
+        while d <= v {
+            let q = v / d;
+            let next = v / q + 1;
+            let m = iif!(q <= limit => small[q as usize]; large[k * d as usize]);
+            sum -= (next - d) as i64 * m;
+            d = next;
+        }
+        large[k] = sum;
+    }
+    large[1] as i32
+}
+
+/// Table of `totient(i)` for every `i` in `0..=limit`, filled with a linear sieve.
+#[wasm_bindgen(js_name = totientTable)]
+pub fn totient_table(limit: u32) -> Vec<u32> {
+    linear_sieve(limit).0
+}
+
+/// Table of `carmichael_lambda(i)` for every `i` in `0..=limit`, filled with a linear sieve.
+#[wasm_bindgen(js_name = carmichaelLambdaTable)]
+pub fn carmichael_lambda_table(limit: u32) -> Vec<u32> {
+    let (phi, lowest) = linear_sieve(limit);
+    let mut lambda = phi.clone();
+    (2..lambda.len()).for_each(|i| {
+        let power = lowest[i] as usize;
+        lambda[i] = match power == i {
+            true => iif!(i >= 8 && i.is_power_of_two() => phi[i] / 2; phi[i]),
+            _ => lcm64(lambda[power] as u64, lambda[i / power] as u64)
+                .expect("lambda(i) divides totient(i), which fits in a u32")
+                as u32,
+        }
+    });
+    lambda
+}
+
+/// Carmichael's function of the prime power `power = p^e`.
+fn prime_power_lambda(power: u64, p: u64) -> u64 {
+    let phi = power / p * (p - 1);
+    iif!(p == 2 && power >= 8 => phi / 2; phi)
+}
+
+/// Table of `mobius(i)` for every `i` in `0..=limit`, filled with a linear sieve.
+fn mobius_table(limit: u32) -> Vec<i8> {
+    let limit = limit as usize;
+    let mut mu = vec![1; limit + 1];
+    let mut composite = vec![false; limit + 1];
+    let mut primes: Vec<usize> = Vec::new();
+    mu[0] = 0;
+    for i in 2..=limit {
+        if !composite[i] {
+            mu[i] = -1;
+            primes.push(i);
+        }
+        for &p in &primes {
+            let multiple = i * p;
+            if multiple > limit {
+                break;
+            }
+            composite[multiple] = true;
+            if i % p == 0 {
+                mu[multiple] = 0;
+                break;
+            }
+            mu[multiple] = -mu[i];
+        }
+    }
+    mu
+}
+
+/// Linear sieve up to `limit`, returning the totient of every number and the power of its smallest
+/// prime factor that divides it.
+fn linear_sieve(limit: u32) -> (Vec<u32>, Vec<u32>) {
+    let limit = limit as usize;
+    let mut phi = vec![0; limit + 1];
+    let mut lowest = vec![0; limit + 1];
+    let mut primes: Vec<usize> = Vec::new();
+    if limit >= 1 {
+        (phi[1], lowest[1]) = (1, 1);
+    }
+    for i in 2..=limit {
+        if lowest[i] == 0 {
+            (phi[i], lowest[i]) = (i as u32 - 1, i as u32);
+            primes.push(i);
+        }
+        for &p in &primes {
+            let multiple = i * p;
+            if multiple > limit {
+                break;
+            }
+            if i % p == 0 {
+                phi[multiple] = phi[i] * p as u32;
+                lowest[multiple] = lowest[i] * p as u32;
+                break;
+            }
+            phi[multiple] = phi[i] * (p as u32 - 1);
+            lowest[multiple] = p as u32;
+        }
+    }
+    (phi, lowest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modular::gcd64;
+
+    fn brute_totient(n: u32) -> u32 {
+        (1..=n).filter(|&k| gcd64(k as u64, n as u64) == 1).count() as u32
+    }
+
+    fn brute_lambda(n: u32) -> u32 {
+        let coprimes: Vec<u32> = (1..=n)
+            .filter(|&k| gcd64(k as u64, n as u64) == 1)
+            .collect();
+        (1..=n)
+            .find(|&m| {
+                coprimes
+                    .iter()
+                    .all(|&a| (0..m).fold(1 % n, |x, _| x * a % n) == 1 % n)
+            })
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn totient_0() {
+        assert_eq!(0, totient(0));
+    }
+
+    #[test]
+    fn totient_1() {
+        assert_eq!(1, totient(1));
+    }
+
+    #[test]
+    fn totient_36() {
+        assert_eq!(12, totient(36));
+    }
+
+    #[test]
+    fn totient_max_prime() {
+        assert_eq!(4294967290, totient(4294967291));
+    }
+
+    #[test]
+    fn totient_u32_max() {
+        assert_eq!(2147483648, totient(u32::MAX));
+    }
+
+    #[test]
+    fn totient_matches_brute_force() {
+        (1..300).for_each(|n| assert_eq!(brute_totient(n), totient(n), "{}", n));
+    }
+
+    #[test]
+    fn carmichael_lambda_0() {
+        assert_eq!(0, carmichael_lambda(0));
+    }
+
+    #[test]
+    fn carmichael_lambda_1() {
+        assert_eq!(1, carmichael_lambda(1));
+    }
+
+    #[test]
+    fn carmichael_lambda_8() {
+        assert_eq!(2, carmichael_lambda(8));
+    }
+
+    #[test]
+    fn carmichael_lambda_561() {
+        assert_eq!(80, carmichael_lambda(561));
+    }
+
+    #[test]
+    fn carmichael_lambda_2_pow_31() {
+        assert_eq!(1 << 29, carmichael_lambda(1 << 31));
+    }
+
+    #[test]
+    fn carmichael_lambda_matches_brute_force() {
+        (1..200).for_each(|n| assert_eq!(brute_lambda(n), carmichael_lambda(n), "{}", n));
+    }
+
+    #[test]
+    fn mobius_0() {
+        assert_eq!(0, mobius(0));
+    }
+
+    #[test]
+    fn mobius_1() {
+        assert_eq!(1, mobius(1));
+    }
+
+    #[test]
+    fn mobius_30() {
+        assert_eq!(-1, mobius(30));
+    }
+
+    #[test]
+    fn mobius_12() {
+        assert_eq!(0, mobius(12));
+    }
+
+    #[test]
+    fn mobius_max_prime() {
+        assert_eq!(-1, mobius(4294967291));
+    }
+
+    #[test]
+    fn mobius_matches_table() {
+        mobius_table(10_000)
+            .into_iter()
+            .zip(0..)
+            .for_each(|(mu, n)| assert_eq!(mobius(n), mu, "{}", n));
+    }
+
+    #[test]
+    fn liouville_0() {
+        assert_eq!(0, liouville(0));
+    }
+
+    #[test]
+    fn liouville_1() {
+        assert_eq!(1, liouville(1));
+    }
+
+    #[test]
+    fn liouville_12() {
+        assert_eq!(-1, liouville(12));
+    }
+
+    #[test]
+    fn liouville_36() {
+        assert_eq!(1, liouville(36));
+    }
+
+    #[test]
+    fn omega_0() {
+        assert_eq!(0, omega(0));
+    }
+
+    #[test]
+    fn omega_1() {
+        assert_eq!(0, omega(1));
+    }
+
+    #[test]
+    fn omega_360() {
+        assert_eq!(3, omega(360));
+    }
+
+    #[test]
+    fn big_omega_1() {
+        assert_eq!(0, big_omega(1));
+    }
+
+    #[test]
+    fn big_omega_360() {
+        assert_eq!(6, big_omega(360));
+    }
+
+    #[test]
+    fn big_omega_2_pow_31() {
+        assert_eq!(31, big_omega(1 << 31));
+    }
+
+    #[test]
+    fn mertens_0() {
+        assert_eq!(0, mertens(0));
+    }
+
+    #[test]
+    fn mertens_1() {
+        assert_eq!(1, mertens(1));
+    }
+
+    #[test]
+    fn mertens_powers_of_10() {
+        [1, -1, 1, 2, -23, -48, 212, 1037, 1928, -222]
+            .into_iter()
+            .zip(0..)
+            .for_each(|(m, e)| assert_eq!(m, mertens(10u32.pow(e)), "10^{}", e));
+    }
+
+    #[test]
+    fn mertens_matches_sum() {
+        (0..3_000).fold(0, |sum, n| {
+            let sum = sum + mobius(n) as i32;
+            assert_eq!(sum, mertens(n), "{}", n);
+            sum
+        });
+    }
+
+    #[test]
+    fn totient_table_0() {
+        assert_eq!(vec![0], totient_table(0));
+    }
+
+    #[test]
+    fn totient_table_10() {
+        assert_eq!(vec![0, 1, 1, 2, 2, 4, 2, 6, 4, 6, 4], totient_table(10));
+    }
+
+    #[test]
+    fn totient_table_matches_totient() {
+        totient_table(100_000)
+            .into_iter()
+            .zip(0..)
+            .for_each(|(phi, n)| assert_eq!(totient(n), phi, "{}", n));
+    }
+
+    #[test]
+    fn carmichael_lambda_table_16() {
+        assert_eq!(
+            vec![0, 1, 1, 2, 2, 4, 2, 6, 2, 6, 4, 10, 2, 12, 6, 4, 4],
+            carmichael_lambda_table(16)
+        );
+    }
+
+    #[test]
+    fn carmichael_lambda_table_matches_carmichael_lambda() {
+        carmichael_lambda_table(100_000)
+            .into_iter()
+            .zip(0..)
+            .for_each(|(lambda, n)| assert_eq!(carmichael_lambda(n), lambda, "{}", n));
+    }
+}
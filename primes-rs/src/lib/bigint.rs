@@ -0,0 +1,600 @@
+use std::cmp::Ordering;
+use std::sync::LazyLock;
+use std::{fmt, iter};
+
+use rand::Rng;
+
+use crate::iif;
+use crate::prime64::PrimeNumber64;
+use crate::sieve::small_primes;
+
+/// Trial divisors tried before the Miller-Rabin rounds, which rule out most candidates cheaply.
+const TRIAL_DIVISION_LIMIT: u32 = 10_000;
+
+/// The primes up to [`TRIAL_DIVISION_LIMIT`], sieved once rather than for every candidate.
+static TRIAL_DIVISORS: LazyLock<Vec<u32>> = LazyLock::new(|| small_primes(TRIAL_DIVISION_LIMIT));
+
+/// `10^19`, the largest power of ten fitting in a limb, so a limb of decimal digits at a time.
+const DECIMAL_CHUNK: u64 = 10_000_000_000_000_000_000;
+
+/// Unsigned integer of any size, stored as little-endian 64-bit limbs without leading zero limbs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct BigUint {
+    limbs: Vec<u64>,
+}
+
+impl BigUint {
+    pub(crate) fn from_u64(num: u64) -> Self {
+        Self::from_limbs(vec![num])
+    }
+
+    /// `2^exp`.
+    pub(crate) fn power_of_two(exp: u64) -> Self {
+        let mut limbs = vec![0; (exp / 64) as usize + 1];
+        limbs[(exp / 64) as usize] = 1 << (exp % 64);
+        Self { limbs }
+    }
+
+    /// Uniform below `2^bits`.
+    pub(crate) fn random<R: Rng + ?Sized>(rng: &mut R, bits: u64) -> Self {
+        let limbs = (0..bits.div_ceil(64)).map(|_| rng.next_u64()).collect();
+        Self::from_limbs(limbs).low_bits(bits)
+    }
+
+    fn from_limbs(mut limbs: Vec<u64>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        Self { limbs }
+    }
+
+    pub(crate) fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub(crate) fn is_odd(&self) -> bool {
+        self.limbs.first().is_some_and(|low| low & 1 == 1)
+    }
+
+    /// The value, if it fits in a `u64`.
+    pub(crate) fn to_u64(&self) -> Option<u64> {
+        match self.limbs[..] {
+            [] => Some(0),
+            [num] => Some(num),
+            _ => None,
+        }
+    }
+
+    /// Whether the bit of weight `2^bit` is set.
+    pub(crate) fn bit(&self, bit: u64) -> bool {
+        self.limbs
+            .get((bit / 64) as usize)
+            .is_some_and(|limb| limb >> (bit % 64) & 1 == 1)
+    }
+
+    pub(crate) fn set_bit(&mut self, bit: u64) {
+        let index = (bit / 64) as usize;
+        if self.limbs.len() <= index {
+            self.limbs.resize(index + 1, 0);
+        }
+        self.limbs[index] |= 1 << (bit % 64);
+    }
+
+    /// Amount of trailing zero bits, `0` for zero.
+    pub(crate) fn trailing_zeros(&self) -> u64 {
+        self.limbs
+            .iter()
+            .position(|&limb| limb != 0)
+            .map_or(0, |i| i as u64 * 64 + self.limbs[i].trailing_zeros() as u64)
+    }
+
+    /// Amount of significant bits.
+    pub(crate) fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            None => 0,
+            Some(top) => self.limbs.len() as u64 * 64 - top.leading_zeros() as u64,
+        }
+    }
+
+    pub(crate) fn add(&self, other: &Self) -> Self {
+        let (long, short) = iif! {
+            self.limbs.len() >= other.limbs.len() => (&self.limbs, &other.limbs);
+            (&other.limbs, &self.limbs)
+        };
+        let mut carry = false;
+        let mut limbs: Vec<u64> = long
+            .iter()
+            .enumerate()
+            .map(|(i, &a)| {
+                let (sum, first) = a.overflowing_add(short.get(i).copied().unwrap_or(0));
+                let (sum, second) = sum.overflowing_add(carry as u64);
+                carry = first || second;
+                sum
+            })
+            .collect();
+        if carry {
+            limbs.push(1);
+        }
+        Self { limbs }
+    }
+
+    /// `self - other`, which must not be negative.
+    pub(crate) fn sub(&self, other: &Self) -> Self {
+        debug_assert!(*self >= *other);
+        let mut borrow = false;
+        let limbs = self
+            .limbs
+            .iter()
+            .enumerate()
+            .map(|(i, &a)| {
+                let (diff, first) = a.overflowing_sub(other.limbs.get(i).copied().unwrap_or(0));
+                let (diff, second) = diff.overflowing_sub(borrow as u64);
+                borrow = first || second;
+                diff
+            })
+            .collect();
+        Self::from_limbs(limbs)
+    }
+
+    /// Schoolbook product, quadratic in the amount of limbs.
+    pub(crate) fn mul(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return Self::default();
+        }
+        let mut limbs = vec![0u64; self.limbs.len() + other.limbs.len()];
+        self.limbs.iter().enumerate().for_each(|(i, &a)| {
+            let mut carry = 0u128;
+            other.limbs.iter().enumerate().for_each(|(j, &b)| {
+                let product = a as u128 * b as u128 + limbs[i + j] as u128 + carry;
+                limbs[i + j] = product as u64;
+                carry = product >> 64;
+            });
+            limbs[i + other.limbs.len()] = carry as u64;
+        });
+        Self::from_limbs(limbs)
+    }
+
+    /// `self >> bits`.
+    pub(crate) fn shr(&self, bits: u64) -> Self {
+        let (skip, shift) = ((bits / 64) as usize, bits % 64);
+        let rest = self.limbs.get(skip..).unwrap_or_default();
+        let limbs = rest
+            .iter()
+            .enumerate()
+            .map(|(i, &limb)| match shift {
+                0 => limb,
+                s => limb >> s | rest.get(i + 1).map_or(0, |&next| next << (64 - s)),
+            })
+            .collect();
+        Self::from_limbs(limbs)
+    }
+
+    /// `self << bits`.
+    pub(crate) fn shl(&self, bits: u64) -> Self {
+        let (skip, shift) = ((bits / 64) as usize, bits % 64);
+        let mut limbs = vec![0; skip];
+        limbs.extend((0..=self.limbs.len()).map(|i| {
+            let low = self.limbs.get(i).map_or(0, |&limb| limb << shift);
+            match (i, shift) {
+                (0, _) | (_, 0) => low,
+                (i, s) => low | self.limbs[i - 1] >> (64 - s),
+            }
+        }));
+        Self::from_limbs(limbs)
+    }
+
+    /// `self mod 2^bits`.
+    pub(crate) fn low_bits(&self, bits: u64) -> Self {
+        let (keep, shift) = ((bits / 64) as usize, bits % 64);
+        let mut limbs: Vec<u64> = self.limbs.iter().take(keep + 1).copied().collect();
+        if limbs.len() > keep {
+            limbs[keep] &= (1 << shift) - 1;
+        }
+        Self::from_limbs(limbs)
+    }
+
+    /// Quotient and remainder of `self / divisor`, which must not be zero. Shifts and subtracts one
+    /// quotient bit at a time, so it suits the small quotients of Euclid's algorithm rather than
+    /// repeated reductions, which Montgomery multiplication does without dividing.
+    pub(crate) fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        debug_assert!(!divisor.is_zero());
+        if self < divisor {
+            return (Self::default(), self.clone());
+        }
+        let shift = self.bits() - divisor.bits();
+        let (mut quotient, mut rest) = (Self::default(), self.clone());
+        let mut shifted = divisor.shl(shift);
+        for bit in (0..=shift).rev() {
+            if rest >= shifted {
+                rest = rest.sub(&shifted);
+                quotient.set_bit(bit);
+            }
+            shifted = shifted.shr(1);
+        }
+        (quotient, rest)
+    }
+
+    /// Quotient and remainder of `self / divisor` for a non-zero single limb divisor.
+    pub(crate) fn div_rem_u64(&self, divisor: u64) -> (Self, u64) {
+        let mut rest = 0u128;
+        let mut limbs: Vec<u64> = self
+            .limbs
+            .iter()
+            .rev()
+            .map(|&limb| {
+                let num = rest << 64 | limb as u128;
+                rest = num % divisor as u128;
+                (num / divisor as u128) as u64
+            })
+            .collect();
+        limbs.reverse();
+        (Self::from_limbs(limbs), rest as u64)
+    }
+
+    /// `self mod divisor` for a non-zero `divisor`, with native divisions on half limbs.
+    fn rem_u32(&self, divisor: u32) -> u32 {
+        let divisor = divisor as u64;
+        self.limbs.iter().rev().fold(0, |rest, &limb| {
+            let rest = (rest << 32 | limb >> 32) % divisor;
+            (rest << 32 | limb & 0xffff_ffff) % divisor
+        }) as u32
+    }
+
+    /// Whether `self` is prime: exactly below `2^64`, above it after trial division and `rounds`
+    /// Miller-Rabin rounds with random bases, so a composite passes with probability below
+    /// `4^-rounds`.
+    pub(crate) fn is_probable_prime<R: Rng + ?Sized>(&self, rounds: u32, rng: &mut R) -> bool {
+        if let Some(num) = self.to_u64() {
+            return PrimeNumber64::is_prime(num);
+        }
+        if TRIAL_DIVISORS.iter().any(|&p| self.rem_u32(p) == 0) {
+            return false;
+        }
+        let one = Self::from_u64(1);
+        let minus_one = self.sub(&one);
+        let twos = minus_one.trailing_zeros();
+        let odd = minus_one.shr(twos);
+        let (two, below) = (Self::from_u64(2), minus_one.sub(&one));
+        let montgomery = Montgomery::new(self);
+        let minus_one = montgomery.to_form(&minus_one);
+        (0..rounds).all(|_| {
+            let base = iter::repeat_with(|| Self::random(rng, self.bits()))
+                .find(|base| two <= *base && *base <= below)
+                .unwrap_or_default();
+            let mut x = montgomery.pow(&montgomery.to_form(&base), &odd);
+            if x == montgomery.one || x == minus_one {
+                return true;
+            }
+            (1..twos).any(|_| {
+                x = montgomery.mul(&x, &x);
+                x == minus_one
+            })
+        })
+    }
+
+    /// The limbs padded with zeros to `len`.
+    fn padded(&self, len: usize) -> Vec<u64> {
+        let mut limbs = self.limbs.clone();
+        limbs.resize(len, 0);
+        limbs
+    }
+}
+
+/// Arithmetic modulo an odd `modulus` on numbers kept as `x R mod modulus` with `R = 2^(64 n)` for
+/// `n` limbs, whose products reduce with shifts instead of divisions.
+struct Montgomery {
+    modulus: BigUint,
+    /// `-modulus^-1 mod 2^64`.
+    inverse: u64,
+    /// `R^2 mod modulus`, which brings numbers into the form.
+    square: Vec<u64>,
+    /// `R mod modulus`, the form of `1`.
+    one: Vec<u64>,
+}
+
+impl Montgomery {
+    fn new(modulus: &BigUint) -> Self {
+        debug_assert!(modulus.is_odd());
+        let len = modulus.limbs.len();
+        let low = modulus.limbs[0];
+        // Every Newton step doubles the amount of correct low bits, from 1 to 64.
+        let inverse = (0..6).fold(1u64, |x, _| {
+            x.wrapping_mul(2u64.wrapping_sub(low.wrapping_mul(x)))
+        });
+        let power = |exp| BigUint::power_of_two(exp).div_rem(modulus).1.padded(len);
+        Self {
+            modulus: modulus.clone(),
+            inverse: inverse.wrapping_neg(),
+            square: power(128 * len as u64),
+            one: power(64 * len as u64),
+        }
+    }
+
+    fn to_form(&self, num: &BigUint) -> Vec<u64> {
+        let reduced = iif!(*num >= self.modulus => num.div_rem(&self.modulus).1; num.clone());
+        self.mul(&reduced.padded(self.square.len()), &self.square)
+    }
+
+    /// `a b R^-1 mod modulus`, interleaving the product and the reduction limb by limb.
+    fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let (modulus, len) = (&self.modulus.limbs, a.len());
+        let mut t = vec![0u64; len + 2];
+        b.iter().for_each(|&limb| {
+            let mut carry = 0u128;
+            t.iter_mut().zip(a).for_each(|(t, &a)| {
+                let sum = *t as u128 + a as u128 * limb as u128 + carry;
+                *t = sum as u64;
+                carry = sum >> 64;
+            });
+            let sum = t[len] as u128 + carry;
+            (t[len], t[len + 1]) = (sum as u64, (sum >> 64) as u64);
+            // Adding `m modulus` clears the lowest limb, which the shift then drops.
+            let m = t[0].wrapping_mul(self.inverse);
+            let mut carry = (t[0] as u128 + m as u128 * modulus[0] as u128) >> 64;
+            (1..len).for_each(|j| {
+                let sum = t[j] as u128 + m as u128 * modulus[j] as u128 + carry;
+                t[j - 1] = sum as u64;
+                carry = sum >> 64;
+            });
+            let sum = t[len] as u128 + carry;
+            (t[len - 1], t[len]) = (sum as u64, t[len + 1] + (sum >> 64) as u64);
+        });
+        // The result is below twice the modulus, so one subtraction reduces it.
+        if t[len] != 0 || t[..len].iter().rev().cmp(modulus.iter().rev()) != Ordering::Less {
+            let mut borrow = false;
+            t.iter_mut().zip(modulus).for_each(|(t, &m)| {
+                let (diff, first) = t.overflowing_sub(m);
+                let (diff, second) = diff.overflowing_sub(borrow as u64);
+                *t = diff;
+                borrow = first || second;
+            });
+        }
+        t.truncate(len);
+        t
+    }
+
+    /// Left to right over the 4-bit windows of `exp`, with the powers `base^0..base^16` at hand.
+    fn pow(&self, base: &[u64], exp: &BigUint) -> Vec<u64> {
+        let powers: Vec<Vec<u64>> =
+            iter::successors(Some(self.one.clone()), |x| Some(self.mul(x, base)))
+                .take(16)
+                .collect();
+        (0..exp.bits().div_ceil(4))
+            .rev()
+            .fold(self.one.clone(), |x, window| {
+                let x = (0..4).fold(x, |x, _| self.mul(&x, &x));
+                let digit = (0..4).fold(0, |digit, i| {
+                    digit | (exp.bit(4 * window + i) as usize) << i
+                });
+                iif!(digit == 0 => x; self.mul(&x, &powers[digit]))
+            })
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (mut chunks, mut rest) = (Vec::new(), self.clone());
+        loop {
+            let (quotient, chunk) = rest.div_rem_u64(DECIMAL_CHUNK);
+            chunks.push(chunk);
+            if quotient.is_zero() {
+                break;
+            }
+            rest = quotient;
+        }
+        let (top, lower) = chunks.split_last().unwrap_or((&0, &[]));
+        write!(f, "{}", top)?;
+        lower
+            .iter()
+            .rev()
+            .try_for_each(|chunk| write!(f, "{:019}", chunk))
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn big(num: u128) -> BigUint {
+        BigUint::from_limbs(vec![num as u64, (num >> 64) as u64])
+    }
+
+    #[test]
+    fn from_u64_0() {
+        assert!(BigUint::from_u64(0).is_zero());
+    }
+
+    #[test]
+    fn power_of_two_bits() {
+        [0, 1, 63, 64, 65, 1000]
+            .into_iter()
+            .for_each(|exp| assert_eq!(exp + 1, BigUint::power_of_two(exp).bits()));
+    }
+
+    #[test]
+    fn add_carry() {
+        assert_eq!(
+            big(1 << 64),
+            BigUint::from_u64(u64::MAX).add(&BigUint::from_u64(1))
+        );
+    }
+
+    #[test]
+    fn add_carry_out() {
+        assert_eq!(
+            BigUint::power_of_two(128),
+            big(u128::MAX).add(&BigUint::from_u64(1))
+        );
+    }
+
+    #[test]
+    fn sub_borrow() {
+        assert_eq!(
+            BigUint::from_u64(u64::MAX),
+            big(1 << 64).sub(&BigUint::from_u64(1))
+        );
+    }
+
+    #[test]
+    fn sub_to_0() {
+        assert!(big(u128::MAX).sub(&big(u128::MAX)).is_zero());
+    }
+
+    #[test]
+    fn mul_matches_u128() {
+        [(0, 5), (3, 7), (u64::MAX, u64::MAX), (1 << 63, 6)]
+            .into_iter()
+            .for_each(|(a, b)| {
+                assert_eq!(
+                    big(a as u128 * b as u128),
+                    BigUint::from_u64(a).mul(&BigUint::from_u64(b))
+                );
+            });
+    }
+
+    #[test]
+    fn mul_powers_of_two() {
+        assert_eq!(
+            BigUint::power_of_two(1000),
+            BigUint::power_of_two(400).mul(&BigUint::power_of_two(600))
+        );
+    }
+
+    #[test]
+    fn shr_matches_u128() {
+        let num = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210u128;
+        [0, 1, 63, 64, 65, 127, 128, 200]
+            .into_iter()
+            .for_each(|bits| {
+                assert_eq!(
+                    big(num.checked_shr(bits).unwrap_or(0)),
+                    big(num).shr(bits as u64)
+                );
+            });
+    }
+
+    #[test]
+    fn low_bits_matches_u128() {
+        let num = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210u128;
+        [0, 1, 63, 64, 65, 127].into_iter().for_each(|bits| {
+            assert_eq!(big(num & ((1 << bits) - 1)), big(num).low_bits(bits as u64));
+        });
+        assert_eq!(big(num), big(num).low_bits(500));
+    }
+
+    #[test]
+    fn cmp_by_length_then_limbs() {
+        assert!(big(1 << 64) > big(u64::MAX as u128));
+        assert!(big(5 << 64 | 1) < big(5 << 64 | 2));
+        assert_eq!(Ordering::Equal, big(7).cmp(&BigUint::from_u64(7)));
+    }
+
+    #[test]
+    fn shl_matches_u128() {
+        let num = 0xfedc_ba98_7654_3210u128;
+        [0, 1, 31, 32, 63, 64].into_iter().for_each(|bits| {
+            assert_eq!(big(num << bits), big(num).shl(bits as u64));
+        });
+        assert_eq!(BigUint::power_of_two(300), BigUint::from_u64(1).shl(300));
+    }
+
+    #[test]
+    fn div_rem_matches_u128() {
+        [
+            (0, 7),
+            (6, 7),
+            (u128::MAX, 3),
+            (1 << 100 | 12345, 1 << 37 | 11),
+            (u128::MAX, u128::MAX),
+        ]
+        .into_iter()
+        .for_each(|(a, b)| {
+            assert_eq!((big(a / b), big(a % b)), big(a).div_rem(&big(b)));
+        });
+    }
+
+    #[test]
+    fn div_rem_u64_matches_u128() {
+        [
+            (0, 7),
+            (u128::MAX, 10),
+            (1 << 100 | 12345, u64::MAX as u128),
+        ]
+        .into_iter()
+        .for_each(|(a, b)| {
+            assert_eq!((big(a / b), (a % b) as u64), big(a).div_rem_u64(b as u64));
+        });
+    }
+
+    #[test]
+    fn to_u64_small() {
+        assert_eq!(Some(0), BigUint::default().to_u64());
+        assert_eq!(Some(u64::MAX), big(u64::MAX as u128).to_u64());
+        assert_eq!(None, big(1 << 64).to_u64());
+    }
+
+    #[test]
+    fn trailing_zeros_powers_of_two() {
+        [0, 1, 63, 64, 200].into_iter().for_each(|exp| {
+            assert_eq!(exp, BigUint::power_of_two(exp).trailing_zeros());
+        });
+    }
+
+    #[test]
+    fn display_decimal() {
+        assert_eq!("0", BigUint::default().to_string());
+        assert_eq!(u128::MAX.to_string(), big(u128::MAX).to_string());
+        assert_eq!(
+            "1606938044258990275541962092341162602522202993782792835301376",
+            BigUint::power_of_two(200).to_string()
+        );
+    }
+
+    fn mersenne(p: u64) -> BigUint {
+        BigUint::power_of_two(p).sub(&BigUint::from_u64(1))
+    }
+
+    #[test]
+    fn is_probable_prime_mersenne() {
+        let mut rng = StdRng::seed_from_u64(0);
+        [61, 89, 107, 127, 521]
+            .into_iter()
+            .for_each(|p| assert!(mersenne(p).is_probable_prime(20, &mut rng), "{}", p));
+        [67, 101, 257]
+            .into_iter()
+            .for_each(|p| assert!(!mersenne(p).is_probable_prime(20, &mut rng), "{}", p));
+    }
+
+    #[test]
+    fn is_probable_prime_above_u64() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let found: Vec<u128> = (1..=51)
+            .filter(|&k| big((1 << 64) + k).is_probable_prime(20, &mut rng))
+            .collect();
+        assert_eq!(vec![13, 37, 51], found);
+    }
+
+    #[test]
+    fn is_probable_prime_semiprime() {
+        let (p, q) = (18446744073709551557u64, 18446744073709551533u64);
+        let product = BigUint::from_u64(p).mul(&BigUint::from_u64(q));
+        assert!(!product.is_probable_prime(20, &mut StdRng::seed_from_u64(0)));
+    }
+}
@@ -0,0 +1,111 @@
+use std::error::Error;
+use std::fmt;
+
+use js_sys::Reflect;
+use wasm_bindgen::prelude::*;
+
+/// Reasons for a computation to fail. On the JavaScript side they are thrown as `Error`s named
+/// `PrimeError`, with the value of [`PrimeError::code`] in their `code` property.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrimeError {
+    /// The beginning of the range is greater than its end.
+    InvalidRange,
+    /// The answer would be greater than the largest supported prime.
+    Overflow,
+    /// There is no prime in the searched range.
+    NotFound,
+    /// The modulus must be prime.
+    NotPrime,
+    /// The modulus is outside of the values accepted by the function.
+    InvalidModulus,
+    /// The value shares a factor with the modulus.
+    NotCoprime,
+    /// The equation has no solution.
+    NoSolution,
+    /// The pattern is empty or some prime divides one of its members for every shift.
+    InvalidPattern,
+}
+
+impl PrimeError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            PrimeError::InvalidRange => "INVALID_RANGE",
+            PrimeError::Overflow => "OVERFLOW",
+            PrimeError::NotFound => "NOT_FOUND",
+            PrimeError::NotPrime => "NOT_PRIME",
+            PrimeError::InvalidModulus => "INVALID_MODULUS",
+            PrimeError::NotCoprime => "NOT_COPRIME",
+            PrimeError::NoSolution => "NO_SOLUTION",
+            PrimeError::InvalidPattern => "INVALID_PATTERN",
+        }
+    }
+}
+
+impl fmt::Display for PrimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PrimeError::InvalidRange => write!(f, "the beginning of the range is after its end"),
+            PrimeError::Overflow => write!(f, "the result exceeds the largest supported prime"),
+            PrimeError::NotFound => write!(f, "there is no prime in the range"),
+            PrimeError::NotPrime => write!(f, "the modulus is not prime"),
+            PrimeError::InvalidModulus => write!(f, "the modulus is not supported"),
+            PrimeError::NotCoprime => write!(f, "the value is not coprime to the modulus"),
+            PrimeError::NoSolution => write!(f, "the equation has no solution"),
+            PrimeError::InvalidPattern => write!(f, "the pattern is empty or not admissible"),
+        }
+    }
+}
+
+impl Error for PrimeError {}
+
+impl From<PrimeError> for JsValue {
+    fn from(error: PrimeError) -> JsValue {
+        let js_error = js_sys::Error::new(&error.to_string());
+        js_error.set_name("PrimeError");
+        Reflect::set(&js_error, &"code".into(), &error.code().into()).unwrap_throw();
+        js_error.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_invalid_range() {
+        assert_eq!("INVALID_RANGE", PrimeError::InvalidRange.code());
+    }
+
+    #[test]
+    fn code_overflow() {
+        assert_eq!("OVERFLOW", PrimeError::Overflow.code());
+    }
+
+    #[test]
+    fn code_not_found() {
+        assert_eq!("NOT_FOUND", PrimeError::NotFound.code());
+    }
+
+    #[test]
+    fn code_not_prime() {
+        assert_eq!("NOT_PRIME", PrimeError::NotPrime.code());
+    }
+
+    #[test]
+    fn code_no_solution() {
+        assert_eq!("NO_SOLUTION", PrimeError::NoSolution.code());
+    }
+
+    #[test]
+    fn code_invalid_pattern() {
+        assert_eq!("INVALID_PATTERN", PrimeError::InvalidPattern.code());
+    }
+
+    #[test]
+    fn display_not_found() {
+        assert_eq!(
+            "there is no prime in the range",
+            PrimeError::NotFound.to_string()
+        );
+    }
+}
@@ -1,10 +1,9 @@
-use std::iter::once;
-
 use wasm_bindgen::prelude::*;
 
+use crate::factorization::factor_pairs;
 use crate::iif;
 use crate::is_factor;
-use crate::util::is_even;
+use crate::prime_int;
 
 #[wasm_bindgen(js_name = isFactor)]
 pub fn is_factor(num: u32, divisor: u32) -> bool {
@@ -28,20 +27,36 @@ pub fn factor_list_in(num: u32, divisors: &[u32]) -> Vec<u32> {
     }
 }
 
+/// See [`prime_int::factor_list`].
 #[wasm_bindgen(js_name = factorList)]
 pub fn factor_list(num: u32) -> Vec<u32> {
+    prime_int::factor_list(num)
+}
+
+#[wasm_bindgen(js_name = divisorCount)]
+pub fn divisor_count(num: u32) -> u32 {
+    match num {
+        0 => 0,
+        n => factor_pairs(n as u64)
+            .into_iter()
+            .map(|(_, e)| e + 1)
+            .product(),
+    }
+}
+
+#[wasm_bindgen(js_name = divisorSum)]
+pub fn divisor_sum(num: u32) -> u64 {
     match num {
-        0 => Vec::new(),
-        1 => vec![1],
-        n => {
-            let inner = iif!(is_even(n) => (2..n).step_by(1); (3..n).step_by(2))
-                .filter(|d| is_factor!(&n, d));
-            once(1).chain(inner).chain(once(n)).collect()
-        }
+        0 => 0,
+        n => factor_pairs(n as u64)
+            .into_iter()
+            .map(|(p, e)| (0..e).fold(1, |sum, _| sum * p + 1))
+            .product(),
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
@@ -140,4 +155,84 @@ mod tests {
     fn factor_list_4() {
         assert_eq!(vec![1, 2, 4], factor_list(4));
     }
+
+    #[test]
+    fn factor_list_12() {
+        assert_eq!(vec![1, 2, 3, 4, 6, 12], factor_list(12));
+    }
+
+    #[test]
+    fn factor_list_max_prime() {
+        assert_eq!(vec![1, 4294967291], factor_list(4294967291));
+    }
+
+    #[test]
+    fn factor_list_u32_max() {
+        assert_eq!(
+            vec![
+                1, 3, 5, 15, 17, 51, 85, 255, 257, 771, 1285, 3855, 4369, 13107, 21845, 65535,
+                65537, 196611, 327685, 983055, 1114129, 3342387, 5570645, 16711935, 16843009,
+                50529027, 84215045, 252645135, 286331153, 858993459, 1431655765, 4294967295
+            ],
+            factor_list(u32::MAX)
+        );
+    }
+
+    #[test]
+    fn factor_list_matches_scan() {
+        (1..2_000).for_each(|n| {
+            let scan: Vec<u32> = (1..=n).filter(|d| is_factor!(n, d)).collect();
+            assert_eq!(scan, factor_list(n));
+        });
+    }
+
+    #[test]
+    fn divisor_count_0() {
+        assert_eq!(0, divisor_count(0));
+    }
+
+    #[test]
+    fn divisor_count_1() {
+        assert_eq!(1, divisor_count(1));
+    }
+
+    #[test]
+    fn divisor_count_12() {
+        assert_eq!(6, divisor_count(12));
+    }
+
+    #[test]
+    fn divisor_count_matches_factor_list() {
+        (0..2_000).chain(u32::MAX - 100..=u32::MAX).for_each(|n| {
+            assert_eq!(factor_list(n).len() as u32, divisor_count(n));
+        });
+    }
+
+    #[test]
+    fn divisor_sum_0() {
+        assert_eq!(0, divisor_sum(0));
+    }
+
+    #[test]
+    fn divisor_sum_1() {
+        assert_eq!(1, divisor_sum(1));
+    }
+
+    #[test]
+    fn divisor_sum_12() {
+        assert_eq!(28, divisor_sum(12));
+    }
+
+    #[test]
+    fn divisor_sum_max_prime() {
+        assert_eq!(4294967292, divisor_sum(4294967291));
+    }
+
+    #[test]
+    fn divisor_sum_matches_factor_list() {
+        (0..2_000).chain(u32::MAX - 100..=u32::MAX).for_each(|n| {
+            let sum: u64 = factor_list(n).into_iter().map(u64::from).sum();
+            assert_eq!(sum, divisor_sum(n));
+        });
+    }
 }
@@ -0,0 +1,306 @@
+use std::cmp::min;
+use std::fmt;
+
+use wasm_bindgen::prelude::*;
+
+use crate::iif;
+use crate::prime_int::{PrimeInt, SMALL_PRIMES, is_prime};
+
+/// Amount of steps multiplied together between two gcd computations in Brent's cycle search.
+const BATCH_SIZE: u64 = 128;
+
+/// Canonical form of a positive number: its distinct prime factors in ascending order, each one
+/// paired with its exponent.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Factorization {
+    factors: Vec<(u32, u32)>,
+}
+
+impl Factorization {
+    pub fn new(factors: Vec<(u32, u32)>) -> Self {
+        Self { factors }
+    }
+
+    pub fn factors(&self) -> &[(u32, u32)] {
+        &self.factors
+    }
+}
+
+#[wasm_bindgen]
+impl Factorization {
+    #[wasm_bindgen(getter)]
+    pub fn primes(&self) -> Vec<u32> {
+        self.factors.iter().map(|&(p, _)| p).collect()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn exponents(&self) -> Vec<u32> {
+        self.factors.iter().map(|&(_, e)| e).collect()
+    }
+
+    #[wasm_bindgen]
+    pub fn product(&self) -> u32 {
+        self.factors.iter().map(|&(p, e)| p.pow(e)).product()
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_js_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for Factorization {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.factors.is_empty() {
+            return write!(f, "1");
+        }
+        self.factors
+            .iter()
+            .enumerate()
+            .try_for_each(|(i, &(p, e))| {
+                if i > 0 {
+                    write!(f, " · ")?;
+                }
+                match e {
+                    1 => write!(f, "{}", p),
+                    e => write!(f, "{}^{}", p, e),
+                }
+            })
+    }
+}
+
+/// Prime factors of `num` paired with their exponents, in ascending order. Small factors are
+/// found by trial division, the remaining cofactor is split with Pollard's rho in Brent's variant,
+/// which takes about `sqrt(p)` steps for its smallest prime factor `p`: around 2 seconds for a
+/// `u128` with two 50-bit factors.
+pub(crate) fn factor_pairs<T: PrimeInt>(num: T) -> Vec<(T, u32)> {
+    if num == T::from(0) {
+        return Vec::new();
+    }
+    let mut rest = num;
+    let mut primes = Vec::new();
+    SMALL_PRIMES
+        .iter()
+        .map(|&p| T::from(p as u8))
+        .for_each(|p| {
+            while rest.has_factor(p) {
+                rest = rest / p;
+                primes.push(p);
+            }
+        });
+    split(rest, &mut primes);
+    primes.sort_unstable();
+    primes
+        .chunk_by(|a, b| a == b)
+        .map(|chunk| (chunk[0], chunk.len() as u32))
+        .collect()
+}
+
+fn split<T: PrimeInt>(num: T, primes: &mut Vec<T>) {
+    match num {
+        n if n == T::from(1) => (),
+        n if is_prime(n) => primes.push(n),
+        n => {
+            let divisor = (1..=u8::MAX)
+                .find_map(|c| brent(n, T::from(c)))
+                .unwrap_or_else(|| trial_divisor(n));
+            debug_assert!(divisor > T::from(1) && divisor < n && n.has_factor(divisor));
+            split(divisor, primes);
+            split(n / divisor, primes);
+        }
+    }
+}
+
+/// Smallest divisor of the odd composite `num`, the last resort when every start of Brent's search
+/// fails, so that `split` never recurses on `num` itself.
+fn trial_divisor<T: PrimeInt>(num: T) -> T {
+    let two = T::from(2);
+    let mut divisor = T::from(3);
+    while !num.has_factor(divisor) {
+        divisor = divisor + two;
+    }
+    divisor
+}
+
+/// Looks for a non trivial divisor of the composite `num` following `x -> x^2 + c (mod num)`.
+fn brent<T: PrimeInt>(num: T, c: T) -> Option<T> {
+    let one = T::from(1);
+    let step = |x: T| x.mul_mod(x, num).add_mod(c, num);
+    let distance = |a: T, b: T| iif!(a > b => a - b; b - a);
+    let (mut x, mut y, mut saved, mut product, mut divisor) =
+        (T::from(0), T::from(2), T::from(2), one, one);
+    let mut length = 1;
+    while divisor == one {
+        x = y;
+        (0..length).for_each(|_| y = step(y));
+        let mut k = 0;
+        while k < length && divisor == one {
+            saved = y;
+            (0..min(BATCH_SIZE, length - k)).for_each(|_| {
+                y = step(y);
+                product = product.mul_mod(distance(x, y), num);
+            });
+            divisor = product.gcd(num);
+            k += BATCH_SIZE;
+        }
+        length *= 2;
+    }
+    if divisor == num {
+        // The batch overshot, retrace it one step at a time.
+        divisor = loop {
+            saved = step(saved);
+            match distance(x, saved).gcd(num) {
+                d if d == one => continue,
+                d => break d,
+            }
+        };
+    }
+    iif!(divisor == num => None; Some(divisor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prime64::PrimeNumber64;
+
+    #[test]
+    fn primes_12() {
+        assert_eq!(
+            vec![2, 3],
+            Factorization::new(vec![(2, 2), (3, 1)]).primes()
+        );
+    }
+
+    #[test]
+    fn exponents_12() {
+        assert_eq!(
+            vec![2, 1],
+            Factorization::new(vec![(2, 2), (3, 1)]).exponents()
+        );
+    }
+
+    #[test]
+    fn product_1() {
+        assert_eq!(1, Factorization::default().product());
+    }
+
+    #[test]
+    fn product_12() {
+        assert_eq!(12, Factorization::new(vec![(2, 2), (3, 1)]).product());
+    }
+
+    #[test]
+    fn product_2_pow_31() {
+        assert_eq!(1 << 31, Factorization::new(vec![(2, 31)]).product());
+    }
+
+    #[test]
+    fn factor_pairs_0() {
+        let empty: Vec<(u64, u32)> = Vec::new();
+        assert_eq!(empty, factor_pairs(0));
+    }
+
+    #[test]
+    fn factor_pairs_1() {
+        let empty: Vec<(u64, u32)> = Vec::new();
+        assert_eq!(empty, factor_pairs(1));
+    }
+
+    #[test]
+    fn factor_pairs_360() {
+        assert_eq!(vec![(2, 3), (3, 2), (5, 1)], factor_pairs(360u64));
+    }
+
+    #[test]
+    fn factor_pairs_carmichael() {
+        assert_eq!(vec![(7, 1), (23, 1), (31, 1)], factor_pairs(4991u64));
+    }
+
+    #[test]
+    fn factor_pairs_semiprime() {
+        assert_eq!(
+            vec![(4294967279, 1), (4294967291, 1)],
+            factor_pairs(4294967279u64 * 4294967291)
+        );
+    }
+
+    #[test]
+    fn factor_pairs_prime_square() {
+        assert_eq!(
+            vec![(4294967291, 2)],
+            factor_pairs(4294967291u64 * 4294967291)
+        );
+    }
+
+    #[test]
+    fn factor_pairs_prime_cube() {
+        assert_eq!(
+            vec![(2097143, 3)],
+            factor_pairs(2097143u64 * 2097143 * 2097143)
+        );
+    }
+
+    #[test]
+    fn factor_pairs_u64_max() {
+        assert_eq!(
+            vec![
+                (3, 1),
+                (5, 1),
+                (17, 1),
+                (257, 1),
+                (641, 1),
+                (65537, 1),
+                (6700417, 1)
+            ],
+            factor_pairs(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn factor_pairs_product() {
+        (1..5_000u64)
+            .chain((1 << 40)..(1 << 40) + 200)
+            .chain(u64::MAX - 200..=u64::MAX)
+            .for_each(|n| {
+                let product = factor_pairs(n)
+                    .into_iter()
+                    .inspect(|&(p, _)| assert!(PrimeNumber64::is_prime(p)))
+                    .map(|(p, e)| p.pow(e))
+                    .product::<u64>();
+                assert_eq!(n, product);
+            });
+    }
+
+    #[test]
+    fn trial_divisor_semiprime() {
+        assert_eq!(53, trial_divisor(53u64 * 59));
+        assert_eq!(65521, trial_divisor(65521u128 * 65537));
+    }
+
+    #[test]
+    fn display_1() {
+        assert_eq!("1", Factorization::default().to_string());
+    }
+
+    #[test]
+    fn display_7() {
+        assert_eq!("7", Factorization::new(vec![(7, 1)]).to_string());
+    }
+
+    #[test]
+    fn display_12() {
+        assert_eq!(
+            "2^2 · 3",
+            Factorization::new(vec![(2, 2), (3, 1)]).to_string()
+        );
+    }
+
+    #[test]
+    fn display_360() {
+        assert_eq!(
+            "2^3 · 3^2 · 5",
+            Factorization::new(vec![(2, 3), (3, 2), (5, 1)]).to_js_string()
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use js_sys::{Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use crate::iif;
+use crate::sieve::Sieve;
+
+/// Lazy sequence of primes starting at any value, ascending up to the largest `u32` prime or
+/// descending down to 2.
+#[wasm_bindgen]
+pub struct Primes {
+    sieve: Rc<RefCell<Sieve>>,
+    asc: bool,
+}
+
+#[wasm_bindgen]
+impl Primes {
+    #[wasm_bindgen(constructor)]
+    pub fn new(start: u32, asc: bool) -> Self {
+        let sieve = iif!(asc => Sieve::new(start, u32::MAX); Sieve::new(0, start));
+        Self {
+            sieve: Rc::new(RefCell::new(sieve)),
+            asc,
+        }
+    }
+
+    #[wasm_bindgen(js_name = next)]
+    pub fn next_result(&mut self) -> Object {
+        let value = self.next();
+        let result = Object::new();
+        Reflect::set(&result, &"done".into(), &value.is_none().into()).unwrap_throw();
+        Reflect::set(&result, &"value".into(), &value.into()).unwrap_throw();
+        result
+    }
+
+    /// Handle on the same position rather than a copy, so that `for…of` advances this iterator as
+    /// JavaScript iterators that are their own iterable do.
+    #[wasm_bindgen(js_name = "[Symbol.iterator]")]
+    pub fn iter(&self) -> Primes {
+        Self {
+            sieve: Rc::clone(&self.sieve),
+            asc: self.asc,
+        }
+    }
+}
+
+/// Independent copy, which advances on its own.
+impl Clone for Primes {
+    fn clone(&self) -> Self {
+        Self {
+            sieve: Rc::new(RefCell::new(self.sieve.borrow().clone())),
+            asc: self.asc,
+        }
+    }
+}
+
+impl Iterator for Primes {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        let mut sieve = self.sieve.borrow_mut();
+        iif!(self.asc => sieve.next(); sieve.next_back())
+    }
+}
+
+impl DoubleEndedIterator for Primes {
+    fn next_back(&mut self) -> Option<u32> {
+        let mut sieve = self.sieve.borrow_mut();
+        iif!(self.asc => sieve.next_back(); sieve.next())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primes_0_asc() {
+        assert_eq!(
+            vec![2, 3, 5, 7, 11],
+            Primes::new(0, true).take(5).collect::<Vec<u32>>()
+        );
+    }
+
+    #[test]
+    fn primes_30_desc() {
+        assert_eq!(
+            vec![29, 23, 19, 17, 13, 11, 7, 5, 3, 2],
+            Primes::new(30, false).collect::<Vec<u32>>()
+        );
+    }
+
+    #[test]
+    fn primes_1_desc() {
+        assert_eq!(None, Primes::new(1, false).next());
+    }
+
+    #[test]
+    fn primes_7_asc() {
+        assert_eq!(Some(7), Primes::new(7, true).next());
+    }
+
+    #[test]
+    fn primes_7_desc() {
+        assert_eq!(Some(7), Primes::new(7, false).next());
+    }
+
+    #[test]
+    fn primes_max_asc() {
+        assert_eq!(
+            vec![4294967279, 4294967291],
+            Primes::new(u32::MAX - 20, true).collect::<Vec<u32>>()
+        );
+    }
+
+    #[test]
+    fn primes_0_asc_next_back() {
+        assert_eq!(Some(4294967291), Primes::new(0, true).next_back());
+    }
+
+    #[test]
+    fn primes_max_desc_next_back() {
+        assert_eq!(
+            vec![2, 3, 5],
+            Primes::new(u32::MAX, false)
+                .rev()
+                .take(3)
+                .collect::<Vec<u32>>()
+        );
+    }
+
+    #[test]
+    fn primes_desc_crosses_segments() {
+        let primes: Vec<u32> = Primes::new(1_000_000, false).collect();
+        assert_eq!(78498, primes.len());
+        assert!(primes.windows(2).all(|w| w[0] > w[1]));
+    }
+
+    #[test]
+    fn primes_clone_is_independent() {
+        let mut primes = Primes::new(0, true);
+        primes.next();
+        assert_eq!(vec![3, 5], primes.clone().take(2).collect::<Vec<u32>>());
+        assert_eq!(Some(3), primes.next());
+    }
+
+    #[test]
+    fn primes_iter_shares_state() {
+        let mut primes = Primes::new(0, true);
+        primes.next();
+        assert_eq!(Some(3), primes.iter().next());
+        assert_eq!(Some(5), primes.next());
+        assert_eq!(vec![7, 11], primes.iter().take(2).collect::<Vec<u32>>());
+        assert_eq!(Some(13), primes.next());
+    }
+}
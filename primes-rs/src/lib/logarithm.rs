@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::PrimeError;
+use crate::factorization::factor_pairs;
+use crate::is_factor;
+use crate::modular::{crt64, mod_inv64};
+use crate::prime_int::PrimeInt;
+use crate::prime64::PrimeNumber64;
+
+/// Largest prime factor of the order of the base accepted by the baby-step giant-step search, which
+/// keeps its table around a million entries.
+const MAX_PRIME_FACTOR: u64 = 1 << 40;
+
+/// Smallest `x` with `g^x = h (mod p)` for a prime `p`, see [`discrete_log64`].
+#[wasm_bindgen(js_name = discreteLog)]
+pub fn discrete_log(g: u32, h: u32, p: u32) -> Result<u32, PrimeError> {
+    discrete_log64(g as u64, h as u64, p as u64).map(|x| x as u32)
+}
+
+/// Smallest `x` with `g^x = h (mod p)` for a prime `p`. Pohlig-Hellman splits the order of `g` into
+/// prime powers and each prime order subgroup is searched with baby-step giant-step, so the order
+/// must be smooth: a prime factor above `2^40` gives [`PrimeError::InvalidModulus`].
+#[wasm_bindgen(js_name = discreteLog64)]
+pub fn discrete_log64(g: u64, h: u64, p: u64) -> Result<u64, PrimeError> {
+    if !PrimeNumber64::is_prime(p) {
+        return Err(PrimeError::NotPrime);
+    }
+    let (g, h) = (g % p, h % p);
+    if g == 0 || h == 0 {
+        return Err(PrimeError::NotCoprime);
+    }
+    let order = order(g, p);
+    let factors = factor_pairs(order);
+    if factors.iter().any(|&(q, _)| q > MAX_PRIME_FACTOR) {
+        return Err(PrimeError::InvalidModulus);
+    }
+    match pohlig_hellman(g, h, order, &factors, p) {
+        Some(x) if g.pow_mod(x, p) == h => Ok(x),
+        _ => Err(PrimeError::NoSolution),
+    }
+}
+
+/// Multiplicative order of `g` modulo the prime `p`.
+fn order(g: u64, p: u64) -> u64 {
+    factor_pairs(p - 1)
+        .into_iter()
+        .fold(p - 1, |mut order, (q, _)| {
+            while is_factor!(order, q) && g.pow_mod(order / q, p) == 1 {
+                order /= q;
+            }
+            order
+        })
+}
+
+/// Solves the logarithm modulo every prime power `q^e` of the order one digit in base `q` at a
+/// time, then combines the parts with the Chinese remainder theorem.
+fn pohlig_hellman(g: u64, h: u64, order: u64, factors: &[(u64, u32)], p: u64) -> Option<u64> {
+    let inverse = mod_inv64(g, p)?;
+    let (residues, moduli): (Vec<u64>, Vec<u64>) = factors
+        .iter()
+        .map(|&(q, e)| {
+            let generator = g.pow_mod(order / q, p);
+            let (mut x, mut power) = (0, 1);
+            for _ in 0..e {
+                let rest = inverse.pow_mod(x, p).mul_mod(h, p);
+                let digit =
+                    baby_step_giant_step(generator, rest.pow_mod(order / power / q, p), q, p)?;
+                x += digit * power;
+                power *= q;
+            }
+            Some((x, power))
+        })
+        .collect::<Option<Vec<(u64, u64)>>>()?
+        .into_iter()
+        .unzip();
+    crt64(&residues, &moduli)
+}
+
+/// Smallest `x < order` with `g^x = h (mod p)`, where `order` is the order of `g`.
+fn baby_step_giant_step(g: u64, h: u64, order: u64, p: u64) -> Option<u64> {
+    let m = order.isqrt() + 1;
+    let mut baby = 1;
+    let mut table = HashMap::with_capacity(m as usize);
+    for j in 0..m {
+        table.entry(baby).or_insert(j);
+        baby = baby.mul_mod(g, p);
+    }
+    let giant = mod_inv64(g.pow_mod(m, p), p)?;
+    let mut gamma = h;
+    for i in 0..m {
+        if let Some(j) = table.get(&gamma) {
+            return Some(i * m + j);
+        }
+        gamma = gamma.mul_mod(giant, p);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discrete_log_not_prime() {
+        assert_eq!(Err(PrimeError::NotPrime), discrete_log(2, 3, 15));
+    }
+
+    #[test]
+    fn discrete_log_not_coprime() {
+        assert_eq!(Err(PrimeError::NotCoprime), discrete_log(7, 3, 7));
+        assert_eq!(Err(PrimeError::NotCoprime), discrete_log(3, 14, 7));
+    }
+
+    #[test]
+    fn discrete_log_not_in_subgroup() {
+        assert_eq!(Err(PrimeError::NoSolution), discrete_log(2, 3, 7));
+    }
+
+    #[test]
+    fn discrete_log_2() {
+        assert_eq!(Ok(0), discrete_log(1, 1, 2));
+    }
+
+    #[test]
+    fn discrete_log_h_1() {
+        assert_eq!(Ok(0), discrete_log(3, 1, 4294967291));
+    }
+
+    #[test]
+    fn discrete_log_3_13_17() {
+        assert_eq!(Ok(4), discrete_log(3, 13, 17));
+    }
+
+    #[test]
+    fn discrete_log_large() {
+        assert_eq!(Ok(1405270687), discrete_log(2, 1234567, 4294967291));
+    }
+
+    #[test]
+    fn discrete_log_matches_brute_force() {
+        [3, 5, 7, 13, 17, 31, 97, 101].into_iter().for_each(|p| {
+            (1..p).for_each(|g| {
+                (1..p).for_each(|h| {
+                    let x = (0..p).find(|&x| (g as u64).pow_mod(x as u64, p as u64) == h as u64);
+                    assert_eq!(x, discrete_log(g, h, p).ok(), "log_{}({}) mod {}", g, h, p);
+                });
+            });
+        });
+    }
+
+    #[test]
+    fn discrete_log64_smooth() {
+        let p = 8608456956238879741;
+        assert_eq!(
+            Ok(4796214510284769841),
+            discrete_log64(18, 123456789123456789, p)
+        );
+    }
+
+    #[test]
+    fn discrete_log64_not_smooth() {
+        assert_eq!(
+            Err(PrimeError::InvalidModulus),
+            discrete_log64(2, 3, 18446744073709551557)
+        );
+    }
+
+    #[test]
+    fn discrete_log64_smooth_subgroup() {
+        let p = 18446744073709551557;
+        let g = 2u64.pow_mod(5594472617641, p);
+        let h = g.pow_mod(1234567, p);
+        let x = discrete_log64(g, h, p).unwrap();
+        assert_eq!(h, g.pow_mod(x, p));
+        assert!(x <= 1234567);
+    }
+}
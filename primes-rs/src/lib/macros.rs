@@ -1,3 +1,6 @@
+/// Whether `d` divides `n`, never for a zero `d`. Works on any primitive integer type, but code
+/// generic over [`crate::prime_int::PrimeInt`] uses its `has_factor` since the literal patterns
+/// cannot match a type parameter.
 #[macro_export]
 macro_rules! is_factor {
     ($n:expr, 0) => (false);
@@ -1,6 +1,20 @@
 extern crate wasm_bindgen;
 
+pub mod arithmetic;
+mod bigint;
+pub mod error;
 pub mod factor;
+pub mod factorization;
+pub mod iter;
+pub mod logarithm;
 pub mod macros;
+pub mod modular;
 pub mod prime;
+pub mod prime64;
+pub mod prime_int;
+pub mod residue;
+pub mod rng;
+pub mod rsa;
+mod sieve;
+pub mod special;
 pub mod util;
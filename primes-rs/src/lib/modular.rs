@@ -0,0 +1,369 @@
+use wasm_bindgen::prelude::*;
+
+use crate::iif;
+use crate::is_factor;
+use crate::prime_int::PrimeInt;
+
+/// Bézout identity `a * x + b * y = gcd` for `u32` inputs.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExtGcd {
+    pub gcd: u32,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Bézout identity `a * x + b * y = gcd` for `u64` inputs.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExtGcd64 {
+    pub gcd: u64,
+    pub x: i64,
+    pub y: i64,
+}
+
+/// `a * b mod modulus`, `None` for a zero modulus.
+#[wasm_bindgen(js_name = modMul)]
+pub fn mod_mul(a: u32, b: u32, modulus: u32) -> Option<u32> {
+    iif!(modulus == 0 => None; Some((a % modulus).mul_mod(b % modulus, modulus)))
+}
+
+/// `a * b mod modulus`, `None` for a zero modulus.
+#[wasm_bindgen(js_name = modMul64)]
+pub fn mod_mul64(a: u64, b: u64, modulus: u64) -> Option<u64> {
+    iif!(modulus == 0 => None; Some((a % modulus).mul_mod(b % modulus, modulus)))
+}
+
+/// `base^exp mod modulus` by square-and-multiply, `None` for a zero modulus.
+#[wasm_bindgen(js_name = modPow)]
+pub fn mod_pow(base: u32, exp: u32, modulus: u32) -> Option<u32> {
+    iif!(modulus == 0 => None; Some(base.pow_mod(exp, modulus)))
+}
+
+/// `base^exp mod modulus` by square-and-multiply, `None` for a zero modulus.
+#[wasm_bindgen(js_name = modPow64)]
+pub fn mod_pow64(base: u64, exp: u64, modulus: u64) -> Option<u64> {
+    iif!(modulus == 0 => None; Some(base.pow_mod(exp, modulus)))
+}
+
+#[wasm_bindgen(js_name = modInv)]
+pub fn mod_inv(num: u32, modulus: u32) -> Option<u32> {
+    mod_inv64(num as u64, modulus as u64).map(|inv| inv as u32)
+}
+
+#[wasm_bindgen(js_name = modInv64)]
+pub fn mod_inv64(num: u64, modulus: u64) -> Option<u64> {
+    if modulus == 0 {
+        return None;
+    }
+    let ExtGcd64 { gcd, x, .. } = ext_gcd64(num % modulus, modulus);
+    iif! {
+        gcd == 1 => Some((x as i128).rem_euclid(modulus as i128) as u64);
+        None
+    }
+}
+
+#[wasm_bindgen]
+pub fn gcd(a: u32, b: u32) -> u32 {
+    gcd64(a as u64, b as u64) as u32
+}
+
+#[wasm_bindgen]
+pub fn gcd64(a: u64, b: u64) -> u64 {
+    iif!(b == 0 => a; gcd64(b, a % b))
+}
+
+/// Least common multiple of `a` and `b`, `None` when it does not fit in a `u32`.
+#[wasm_bindgen]
+pub fn lcm(a: u32, b: u32) -> Option<u32> {
+    lcm64(a as u64, b as u64).and_then(|lcm| u32::try_from(lcm).ok())
+}
+
+/// Least common multiple of `a` and `b`, `None` when it does not fit in a `u64`.
+#[wasm_bindgen]
+pub fn lcm64(a: u64, b: u64) -> Option<u64> {
+    match (a, b) {
+        (0, _) | (_, 0) => Some(0),
+        (a, b) => (a / gcd64(a, b)).checked_mul(b),
+    }
+}
+
+#[wasm_bindgen(js_name = extGcd)]
+pub fn ext_gcd(a: u32, b: u32) -> ExtGcd {
+    let ExtGcd64 { gcd, x, y } = ext_gcd64(a as u64, b as u64);
+    ExtGcd {
+        gcd: gcd as u32,
+        x: x as i32,
+        y: y as i32,
+    }
+}
+
+#[wasm_bindgen(js_name = extGcd64)]
+pub fn ext_gcd64(a: u64, b: u64) -> ExtGcd64 {
+    let (mut old_r, mut r) = (a as i128, b as i128);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    ExtGcd64 {
+        gcd: old_r as u64,
+        x: old_x as i64,
+        y: old_y as i64,
+    }
+}
+
+/// Smallest `x` satisfying `x = residues[i] (mod moduli[i])` for every `i`, the moduli need not be
+/// coprime. `None` when the congruences are inconsistent or their combined modulus does not fit in
+/// a `u32`.
+#[wasm_bindgen]
+pub fn crt(residues: &[u32], moduli: &[u32]) -> Option<u32> {
+    let widen = |list: &[u32]| list.iter().map(|&n| n as u64).collect::<Vec<u64>>();
+    match crt_pair(&widen(residues), &widen(moduli))? {
+        (x, modulus) if modulus <= u32::MAX as u64 => Some(x as u32),
+        _ => None,
+    }
+}
+
+/// Smallest `x` satisfying `x = residues[i] (mod moduli[i])` for every `i`, the moduli need not be
+/// coprime. `None` when the congruences are inconsistent or their combined modulus does not fit in
+/// a `u64`.
+#[wasm_bindgen]
+pub fn crt64(residues: &[u64], moduli: &[u64]) -> Option<u64> {
+    crt_pair(residues, moduli).map(|(x, _)| x)
+}
+
+/// Solution of the congruences together with the combined modulus.
+fn crt_pair(residues: &[u64], moduli: &[u64]) -> Option<(u64, u64)> {
+    if residues.len() != moduli.len() {
+        return None;
+    }
+    residues
+        .iter()
+        .zip(moduli)
+        .try_fold((0, 1), |(x, modulus), (&residue, &n)| {
+            if n == 0 {
+                return None;
+            }
+            let g = gcd64(modulus, n);
+            let diff = (((residue % n) as u128 + (n - x % n) as u128) % n as u128) as u64;
+            if !is_factor!(diff, g) {
+                return None;
+            }
+            let step = n / g;
+            let k = (diff / g).mul_mod(mod_inv64(modulus / g % step, step)?, step);
+            let combined = modulus.checked_mul(step)?;
+            Some(((x as u128 + modulus as u128 * k as u128) as u64, combined))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mod_mul_large() {
+        assert_eq!(Some(1), mod_mul(u32::MAX - 1, u32::MAX - 1, u32::MAX));
+    }
+
+    #[test]
+    fn mod_mul64_large() {
+        assert_eq!(Some(1), mod_mul64(u64::MAX - 1, u64::MAX - 1, u64::MAX));
+    }
+
+    #[test]
+    fn mod_mul_modulus_0() {
+        assert_eq!(None, mod_mul(3, 4, 0));
+        assert_eq!(None, mod_mul64(3, 4, 0));
+    }
+
+    #[test]
+    fn mod_pow_modulus_0() {
+        assert_eq!(None, mod_pow(3, 4, 0));
+        assert_eq!(None, mod_pow64(3, 4, 0));
+        assert_eq!(None, mod_pow64(3, 0, 0));
+    }
+
+    #[test]
+    fn mod_pow_0_0() {
+        assert_eq!(Some(1), mod_pow(0, 0, 7));
+    }
+
+    #[test]
+    fn mod_pow_mod_1() {
+        assert_eq!(Some(0), mod_pow(5, 0, 1));
+    }
+
+    #[test]
+    fn mod_pow_fermat() {
+        assert_eq!(Some(1), mod_pow(2, 4294967290, 4294967291));
+    }
+
+    #[test]
+    fn mod_pow64_fermat() {
+        assert_eq!(
+            Some(1),
+            mod_pow64(3, 18446744073709551556, 18446744073709551557)
+        );
+    }
+
+    #[test]
+    fn mod_pow64_small() {
+        assert_eq!(Some(445), mod_pow64(4, 13, 497));
+    }
+
+    #[test]
+    fn mod_inv_3_11() {
+        assert_eq!(Some(4), mod_inv(3, 11));
+    }
+
+    #[test]
+    fn mod_inv_not_coprime() {
+        assert_eq!(None, mod_inv(6, 9));
+    }
+
+    #[test]
+    fn mod_inv_above_modulus() {
+        assert_eq!(Some(4), mod_inv(14, 11));
+    }
+
+    #[test]
+    fn mod_inv64_large() {
+        let modulus = 18446744073709551557;
+        let inv = mod_inv64(u64::MAX, modulus).unwrap();
+        assert_eq!(Some(1), mod_mul64(u64::MAX, inv, modulus));
+    }
+
+    #[test]
+    fn mod_inv_mod_0() {
+        assert_eq!(None, mod_inv(3, 0));
+    }
+
+    #[test]
+    fn gcd_0_0() {
+        assert_eq!(0, gcd(0, 0));
+    }
+
+    #[test]
+    fn gcd_0_5() {
+        assert_eq!(5, gcd(0, 5));
+    }
+
+    #[test]
+    fn gcd_12_18() {
+        assert_eq!(6, gcd(12, 18));
+    }
+
+    #[test]
+    fn gcd64_large() {
+        assert_eq!(4294967291, gcd64(4294967291 * 3, 4294967291 * 5));
+    }
+
+    #[test]
+    fn lcm_0_5() {
+        assert_eq!(Some(0), lcm(0, 5));
+    }
+
+    #[test]
+    fn lcm_4_6() {
+        assert_eq!(Some(12), lcm(4, 6));
+    }
+
+    #[test]
+    fn lcm_overflow() {
+        assert_eq!(None, lcm(u32::MAX, u32::MAX - 1));
+    }
+
+    #[test]
+    fn lcm64_overflow() {
+        assert_eq!(None, lcm64(u64::MAX, u64::MAX - 1));
+    }
+
+    #[test]
+    fn ext_gcd_240_46() {
+        assert_eq!(
+            ExtGcd {
+                gcd: 2,
+                x: -9,
+                y: 47
+            },
+            ext_gcd(240, 46)
+        );
+    }
+
+    #[test]
+    fn ext_gcd_5_0() {
+        assert_eq!(ExtGcd { gcd: 5, x: 1, y: 0 }, ext_gcd(5, 0));
+    }
+
+    #[test]
+    fn ext_gcd_bezout() {
+        [(u32::MAX, u32::MAX - 1), (4294967291, 65536), (1, u32::MAX)]
+            .into_iter()
+            .for_each(|(a, b)| {
+                let ExtGcd { gcd, x, y } = ext_gcd(a, b);
+                assert_eq!(gcd as i64, a as i64 * x as i64 + b as i64 * y as i64);
+            });
+    }
+
+    #[test]
+    fn ext_gcd64_bezout() {
+        [(u64::MAX, u64::MAX - 1), (18446744073709551557, 1 << 40)]
+            .into_iter()
+            .for_each(|(a, b)| {
+                let ExtGcd64 { gcd, x, y } = ext_gcd64(a, b);
+                assert_eq!(gcd as i128, a as i128 * x as i128 + b as i128 * y as i128);
+            });
+    }
+
+    #[test]
+    fn crt_empty() {
+        assert_eq!(Some(0), crt(&[], &[]));
+    }
+
+    #[test]
+    fn crt_coprime() {
+        assert_eq!(Some(23), crt(&[2, 3, 2], &[3, 5, 7]));
+    }
+
+    #[test]
+    fn crt_not_coprime() {
+        assert_eq!(Some(10), crt(&[4, 10], &[6, 12]));
+    }
+
+    #[test]
+    fn crt_inconsistent() {
+        assert_eq!(None, crt(&[1, 2], &[4, 6]));
+    }
+
+    #[test]
+    fn crt_length_mismatch() {
+        assert_eq!(None, crt(&[1, 2], &[4]));
+    }
+
+    #[test]
+    fn crt_zero_modulus() {
+        assert_eq!(None, crt(&[1], &[0]));
+    }
+
+    #[test]
+    fn crt_overflow() {
+        assert_eq!(None, crt(&[1, 2], &[4294967291, 4294967279]));
+    }
+
+    #[test]
+    fn crt64_near_max() {
+        assert_eq!(Some(u64::MAX - 1), crt64(&[u64::MAX - 1], &[u64::MAX]));
+        assert_eq!(None, crt64(&[u64::MAX - 1, 0], &[u64::MAX, 2]));
+    }
+
+    #[test]
+    fn crt64_large() {
+        let (p, q) = (4294967291, 4294967279);
+        let x = crt64(&[1, 2], &[p, q]).unwrap();
+        assert_eq!((1, 2), (x % p, x % q));
+    }
+}
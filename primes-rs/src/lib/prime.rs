@@ -1,116 +1,316 @@
 use std::cmp::Ordering::*;
+use std::cmp::{max, min};
+use std::collections::VecDeque;
+use std::iter;
 
 use rand::prelude::*;
 use wasm_bindgen::prelude::*;
 
+use crate::error::PrimeError;
 use crate::factor::{any_factor_in, factor_list_in};
+use crate::factorization::{Factorization, factor_pairs};
 use crate::iif;
-use crate::is_factor;
-use crate::util::{is_even, sqrt};
+use crate::prime_int;
+use crate::prime64::PrimeNumber64;
+use crate::sieve::{
+    Sieve, WHEEL_PRIMES, constellations, prime_count, small_primes, sophie_germain,
+};
+use crate::util::is_even;
 
 const MAX_PRIME: u32 = 4294967291;
 
+/// Amount of primes up to `MAX_PRIME`.
+const MAX_PRIME_COUNT: u32 = 203280221;
+
+/// Successor rule of a Cunningham chain: `p -> 2p + 1` for the first kind, `p -> 2p - 1` for the
+/// second.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CunninghamKind {
+    First,
+    Second,
+}
+
 #[wasm_bindgen]
 pub struct PrimeNumber;
 
+impl PrimeNumber {
+    /// Pairs `(p, p + 2)` of primes in `begin..=end`.
+    pub fn twin_primes(begin: u32, end: u32) -> Vec<(u32, u32)> {
+        Self::prime_pairs(begin, end, 2)
+    }
+
+    /// Pairs `(p, p + 4)` of primes in `begin..=end`.
+    pub fn cousin_primes(begin: u32, end: u32) -> Vec<(u32, u32)> {
+        Self::prime_pairs(begin, end, 4)
+    }
+
+    /// Pairs `(p, p + 6)` of primes in `begin..=end`, whether or not another prime lies between.
+    pub fn sexy_primes(begin: u32, end: u32) -> Vec<(u32, u32)> {
+        Self::prime_pairs(begin, end, 6)
+    }
+
+    /// Pairs `(p, p + gap)` of primes in `begin..=end`, found in a single pass of the sieve by
+    /// keeping the primes of the last `gap` numbers.
+    pub fn prime_pairs(begin: u32, end: u32, gap: u32) -> Vec<(u32, u32)> {
+        if gap == 0 {
+            return Vec::new();
+        }
+        let mut window: VecDeque<u32> = VecDeque::new();
+        Sieve::new(begin, end)
+            .filter_map(|q| {
+                while window.front().is_some_and(|&p| q - p > gap) {
+                    window.pop_front();
+                }
+                let pair = window.front().filter(|&&p| q - p == gap).map(|&p| (p, q));
+                window.push_back(q);
+                pair
+            })
+            .collect()
+    }
+
+    /// [`PrimeNumber::try_random_range`] drawing from `rng`.
+    pub(crate) fn random_range_with<R: Rng + ?Sized>(
+        rng: &mut R,
+        begin: u32,
+        end: u32,
+    ) -> Result<u32, PrimeError> {
+        match begin.cmp(&end) {
+            Greater => Err(PrimeError::InvalidRange),
+            Equal => Self::prime(begin).ok_or(PrimeError::NotFound),
+            _ => {
+                let orig = rng.random_range(begin..=end);
+                if Self::is_prime(orig) {
+                    return Ok(orig);
+                }
+                let above = orig
+                    .checked_add(1)
+                    .and_then(|n| Self::closest_prime(n, true))
+                    .filter(|&n| n <= end);
+                iif! {
+                    above;
+                    orig.checked_sub(1)
+                        .and_then(|n| Self::closest_prime(n, false))
+                        .filter(|&n| n >= begin)
+                }
+                .ok_or(PrimeError::NotFound)
+            }
+        }
+    }
+
+    /// [`PrimeNumber::try_random_range_uniform`] drawing from `rng`: random numbers of the range are
+    /// rejected until one is prime, which takes `ln(end)` draws on average.
+    pub(crate) fn random_range_uniform_with<R: Rng + ?Sized>(
+        rng: &mut R,
+        begin: u32,
+        end: u32,
+    ) -> Result<u32, PrimeError> {
+        if begin > end {
+            return Err(PrimeError::InvalidRange);
+        }
+        // Without a prime in the range the rejection loop would never end.
+        Self::closest_prime(begin, true)
+            .filter(|&p| p <= end)
+            .ok_or(PrimeError::NotFound)?;
+        iter::repeat_with(|| rng.random_range(begin..=end))
+            .find(|&n| Self::is_prime(n))
+            .ok_or(PrimeError::NotFound)
+    }
+
+    /// [`PrimeNumber::random_safe_prime`] drawing from `rng`: one of the safe primes sieved out of
+    /// the range, so memory grows with their number.
+    pub(crate) fn random_safe_prime_with<R: Rng + ?Sized>(
+        rng: &mut R,
+        begin: u32,
+        end: u32,
+    ) -> Result<u32, PrimeError> {
+        if begin > end {
+            return Err(PrimeError::InvalidRange);
+        }
+        let found = Self::safe_primes(begin, end);
+        match found.len() {
+            0 => Err(PrimeError::NotFound),
+            len => Ok(found[rng.random_range(0..len)]),
+        }
+    }
+
+    /// [`PrimeNumber::try_random`] drawing from `rng`.
+    pub(crate) fn random_with<R: Rng + ?Sized>(rng: &mut R) -> Result<u32, PrimeError> {
+        match rng.random() {
+            n @ 0..=99 => Self::try_closest_prime(n, true),
+            n if n >= MAX_PRIME => Ok(MAX_PRIME),
+            n => Self::try_closest_prime(n, rng.random()),
+        }
+    }
+}
+
 #[wasm_bindgen]
 impl PrimeNumber {
+    /// See [`prime_int::range_list`].
     #[wasm_bindgen(js_name = rangeList)]
     pub fn range_list(begin: u32, end: u32) -> Vec<u32> {
-        match (begin, end) {
-            (b, e) if b == e => iif!(Self::is_prime(b) => vec![b]; Vec::new()),
-            (b, e) if b > e => Vec::new(),
-            (b, e) => (b..=e).filter(Self::is_prime_ref).collect(),
-        }
+        prime_int::range_list(begin, end)
     }
 
     #[wasm_bindgen]
     pub fn nth(n: u32) -> Option<u32> {
-        match n.cmp(&199999) {
-            Greater => return None, // Very slow
-            Equal => return Some(1299709),
-            _ => (),
+        Self::try_nth(n).ok()
+    }
+
+    #[wasm_bindgen(js_name = tryNth)]
+    pub fn try_nth(n: u32) -> Result<u32, PrimeError> {
+        if n >= MAX_PRIME_COUNT {
+            return Err(PrimeError::Overflow);
         }
-        let mut primes = vec![2, 3, 5, 7, 11, 13, 17, 19, 23];
-        if n > 8 {
-            let mut next = 29u32;
-            let search = |_| match (next..).step_by(2).find(Self::is_prime_ref) {
-                Some(prime) => {
-                    next = prime + 2;
-                    primes.push(prime);
-                    Ok(())
-                }
-                None => Err(()),
-            };
-            if (9..=n).try_for_each(search).is_err() {
-                return None;
-            }
+        let (begin, end) = Self::nth_bounds(n as u64 + 1);
+        let skip = n as u64 - iif!(begin > 0 => prime_count(begin as u64 - 1); 0);
+        Sieve::new(begin, end)
+            .nth(skip as usize)
+            .ok_or(PrimeError::Overflow)
+    }
+
+    #[wasm_bindgen(js_name = primeCount)]
+    pub fn count(x: u32) -> u32 {
+        prime_count(x as u64) as u32
+    }
+
+    #[wasm_bindgen(js_name = primeCountRange)]
+    pub fn count_range(begin: u32, end: u32) -> u32 {
+        match (begin, end) {
+            (b, e) if b > e => 0,
+            (0, e) => Self::count(e),
+            (b, e) => Self::count(e) - Self::count(b - 1),
         }
-        primes.get(n as usize).cloned()
+    }
+
+    /// [`PrimeNumber::twin_primes`] flattened into `[p1, p1 + 2, p2, p2 + 2, ...]`.
+    #[wasm_bindgen(js_name = twinPrimes)]
+    pub fn twin_primes_flat(begin: u32, end: u32) -> Vec<u32> {
+        Self::prime_pairs_flat(begin, end, 2)
+    }
+
+    /// [`PrimeNumber::cousin_primes`] flattened into `[p1, p1 + 4, p2, p2 + 4, ...]`.
+    #[wasm_bindgen(js_name = cousinPrimes)]
+    pub fn cousin_primes_flat(begin: u32, end: u32) -> Vec<u32> {
+        Self::prime_pairs_flat(begin, end, 4)
+    }
+
+    /// [`PrimeNumber::sexy_primes`] flattened into `[p1, p1 + 6, p2, p2 + 6, ...]`.
+    #[wasm_bindgen(js_name = sexyPrimes)]
+    pub fn sexy_primes_flat(begin: u32, end: u32) -> Vec<u32> {
+        Self::prime_pairs_flat(begin, end, 6)
+    }
+
+    /// [`PrimeNumber::prime_pairs`] flattened into `[p1, p1 + gap, p2, p2 + gap, ...]`.
+    #[wasm_bindgen(js_name = primePairs)]
+    pub fn prime_pairs_flat(begin: u32, end: u32, gap: u32) -> Vec<u32> {
+        Self::prime_pairs(begin, end, gap)
+            .into_iter()
+            .flat_map(|(p, q)| [p, q])
+            .collect()
+    }
+
+    /// Every `n` in `begin..=end` such that `n + offset` is a prime no greater than `end` for each
+    /// offset of `pattern`, e.g. `[0, 2, 6]` for prime triplets. The pattern must be admissible, that
+    /// is no prime divides one of `n + offset` for every `n`.
+    #[wasm_bindgen(js_name = primeConstellations)]
+    pub fn prime_constellations(
+        begin: u32,
+        end: u32,
+        pattern: &[u32],
+    ) -> Result<Vec<u32>, PrimeError> {
+        let mut offsets = pattern.to_vec();
+        offsets.sort_unstable();
+        offsets.dedup();
+        if !Self::is_admissible(&offsets) {
+            return Err(PrimeError::InvalidPattern);
+        }
+        let Some(last) = end.checked_sub(offsets[offsets.len() - 1]) else {
+            return Ok(Vec::new());
+        };
+        // Below the wheel primes a member may be one of them, which the wheel would skip.
+        let wheel_end = WHEEL_PRIMES[WHEEL_PRIMES.len() - 1] as u32;
+        let mut found: Vec<u32> = (begin..=min(last, wheel_end))
+            .filter(|&n| offsets.iter().all(|&o| Self::is_prime(n + o)))
+            .collect();
+        found.extend(constellations(max(begin, wheel_end + 1), last, &offsets));
+        Ok(found)
+    }
+
+    /// Safe primes `p = 2q + 1` in `begin..=end`, read off the Sophie Germain primes `q`.
+    #[wasm_bindgen(js_name = safePrimes)]
+    pub fn safe_primes(begin: u32, end: u32) -> Vec<u32> {
+        match (begin, end) {
+            (b, e) if b > e || e < 5 => Vec::new(),
+            (b, e) => sophie_germain(b.saturating_sub(1).div_ceil(2), (e - 1) / 2)
+                .into_iter()
+                .map(|q| 2 * q + 1)
+                .collect(),
+        }
+    }
+
+    /// Safe prime of the range picked with equal probability for every safe prime in it, see
+    /// [`PrimeNumber::safe_primes`].
+    #[wasm_bindgen(js_name = randomSafePrime)]
+    pub fn random_safe_prime(begin: u32, end: u32) -> Result<u32, PrimeError> {
+        Self::random_safe_prime_with(&mut rand::rng(), begin, end)
+    }
+
+    /// Chain of primes starting at `start` and following the rule of `kind` for as long as it stays
+    /// prime, empty when `start` itself is not prime.
+    #[wasm_bindgen(js_name = cunninghamChain)]
+    pub fn cunningham_chain(start: u32, kind: CunninghamKind) -> Vec<u64> {
+        let next = |p: &u64| match kind {
+            CunninghamKind::First => p.checked_mul(2).and_then(|n| n.checked_add(1)),
+            CunninghamKind::Second => p.checked_mul(2).and_then(|n| n.checked_sub(1)),
+        };
+        std::iter::successors(Some(start as u64), next)
+            .take_while(|&p| PrimeNumber64::is_prime(p))
+            .collect()
     }
 
     #[wasm_bindgen(js_name = randomRange)]
     pub fn random_range(begin: u32, end: u32) -> Option<u32> {
-        match begin.cmp(&end) {
-            Greater => None,
-            Equal => Self::prime(begin),
-            _ => {
-                let orig = rand::rng().random_range(begin..=end);
-                if Self::is_prime(orig) {
-                    return Some(orig);
-                }
-                let res = match Self::closest_prime(orig + 1, true) {
-                    Some(n) => match n.cmp(&end) {
-                        Greater => None,
-                        _ => Some(n),
-                    },
-                    None => None,
-                };
-                match res {
-                    Some(n) => Some(n),
-                    _ => match Self::closest_prime(orig - 1, false) {
-                        Some(n) => match n.cmp(&begin) {
-                            Less => None,
-                            _ => Some(n),
-                        },
-                        None => None,
-                    },
-                }
-            }
-        }
+        Self::try_random_range(begin, end).ok()
+    }
+
+    /// Closest prime above a random number of the range, otherwise the closest one below. Cheap, but
+    /// primes that follow a long gap come up more often, see [`PrimeNumber::try_random_range_uniform`].
+    #[wasm_bindgen(js_name = tryRandomRange)]
+    pub fn try_random_range(begin: u32, end: u32) -> Result<u32, PrimeError> {
+        Self::random_range_with(&mut rand::rng(), begin, end)
+    }
+
+    #[wasm_bindgen(js_name = randomRangeUniform)]
+    pub fn random_range_uniform(begin: u32, end: u32) -> Option<u32> {
+        Self::try_random_range_uniform(begin, end).ok()
+    }
+
+    /// Prime of the range picked with equal probability for every prime in it.
+    #[wasm_bindgen(js_name = tryRandomRangeUniform)]
+    pub fn try_random_range_uniform(begin: u32, end: u32) -> Result<u32, PrimeError> {
+        Self::random_range_uniform_with(&mut rand::rng(), begin, end)
     }
 
     #[wasm_bindgen]
     pub fn random() -> Option<u32> {
-        let mut generator = rand::rng();
-        match generator.random() {
-            n @ 0..=99 => Self::closest_prime(n, true),
-            n if n >= MAX_PRIME => Some(MAX_PRIME),
-            n => iif! {
-                generator.random() => Self::closest_prime(n, true);
-                Self::closest_prime(n, false)
-            },
-        }
+        Self::try_random().ok()
+    }
+
+    #[wasm_bindgen(js_name = tryRandom)]
+    pub fn try_random() -> Result<u32, PrimeError> {
+        Self::random_with(&mut rand::rng())
     }
 
     #[wasm_bindgen(js_name = closestPrime)]
     pub fn closest_prime(num: u32, asc: bool) -> Option<u32> {
-        let num = match num {
-            0 | 1 => 1,
-            2 | 3 | MAX_PRIME => return Some(num),
-            n if is_even(n) => iif! {asc => n + 1; n - 1},
-            n => n,
-        };
-        iif! {
-            asc => match num.cmp(&MAX_PRIME) {
-                Less => (num..).step_by(2).find(Self::is_prime_ref),
-                _ => None,
-            };
-            match num.cmp(&3) {
-                Greater => (3..=num).rev().step_by(2).find(Self::is_prime_ref),
-                _ => None,
-            }
-        }
+        Self::try_closest_prime(num, asc).ok()
+    }
+
+    /// See [`prime_int::try_closest_prime`].
+    #[wasm_bindgen(js_name = tryClosestPrime)]
+    pub fn try_closest_prime(num: u32, asc: bool) -> Result<u32, PrimeError> {
+        prime_int::try_closest_prime(num, asc)
     }
 
     #[wasm_bindgen(js_name = anyPrimeFactorIn)]
@@ -129,58 +329,102 @@ impl PrimeNumber {
         }
     }
 
+    /// See [`prime_int::prime_factor_list`].
     #[wasm_bindgen(js_name = primeFactorList)]
     pub fn prime_factor_list(num: u32) -> Vec<u32> {
-        let mut prime = 2;
-        let mut list = Vec::new();
-        loop {
-            if prime > sqrt(num) {
-                if Self::is_prime_ref(&num) {
-                    list.push(num);
-                }
-                break;
-            }
-            if is_factor!(num, prime) {
-                list.push(prime);
-            }
-            match Self::closest_prime(prime + 1, true) {
-                Some(p) => prime = p,
-                _ => break,
-            }
-        }
-        list
+        prime_int::prime_factor_list(num)
+    }
+
+    #[wasm_bindgen]
+    pub fn factorize(num: u32) -> Option<Factorization> {
+        iif!(num == 0 => None; Some(Factorization::new(factor_pairs(num))))
     }
 
     #[wasm_bindgen]
     pub fn prime(num: u32) -> Option<u32> {
-        iif!(Self::is_prime_ref(&num) => Some(num); None)
+        iif!(Self::is_prime(num) => Some(num); None)
     }
 
     #[wasm_bindgen(js_name = filterPrimes)]
     pub fn prime_list_in(list: &[u32]) -> Vec<u32> {
-        list.iter().cloned().filter(Self::is_prime_ref).collect()
+        list.iter()
+            .cloned()
+            .filter(|&n| Self::is_prime(n))
+            .collect()
     }
 
+    /// See [`prime_int::is_prime`].
     #[wasm_bindgen(js_name = isPrime)]
     pub fn is_prime(num: u32) -> bool {
-        Self::is_prime_ref(&num)
+        prime_int::is_prime(num)
     }
 
-    fn is_prime_ref(num: &u32) -> bool {
-        match num {
-            0 | 1 => false,
-            2 | 3 | 5 | 7 | 11 | 13 | 17 | 19 | 23 => true,
-            n if is_factor!(n, &[2, 3, 5, 7, 11, 13, 17, 19, 23]) => false,
-            &n => !(29..=sqrt(n))
-                .step_by(6)
-                .any(|i| is_factor!(n, &[i, i + 2])),
-        }
+    /// Whether `num` and `num - 2` or `num + 2` are both prime.
+    #[wasm_bindgen(js_name = isTwinPrime)]
+    pub fn is_twin_prime(num: u32) -> bool {
+        Self::has_prime_at_distance(num, 2)
+    }
+
+    /// Whether `num` and `num - 4` or `num + 4` are both prime.
+    #[wasm_bindgen(js_name = isCousinPrime)]
+    pub fn is_cousin_prime(num: u32) -> bool {
+        Self::has_prime_at_distance(num, 4)
+    }
+
+    /// Whether `num` and `num - 6` or `num + 6` are both prime.
+    #[wasm_bindgen(js_name = isSexyPrime)]
+    pub fn is_sexy_prime(num: u32) -> bool {
+        Self::has_prime_at_distance(num, 6)
+    }
+
+    /// Whether `num` and `2 * num + 1` are both prime.
+    #[wasm_bindgen(js_name = isSophieGermain)]
+    pub fn is_sophie_germain(num: u32) -> bool {
+        Self::is_prime(num) && PrimeNumber64::is_prime(2 * num as u64 + 1)
+    }
+
+    /// Whether `num` and `(num - 1) / 2` are both prime.
+    #[wasm_bindgen(js_name = isSafePrime)]
+    pub fn is_safe_prime(num: u32) -> bool {
+        !is_even(num) && Self::is_prime(num / 2) && Self::is_prime(num)
+    }
+
+    fn has_prime_at_distance(num: u32, gap: u32) -> bool {
+        Self::is_prime(num)
+            && (num.checked_sub(gap).is_some_and(Self::is_prime)
+                || num.checked_add(gap).is_some_and(Self::is_prime))
+    }
+
+    /// Whether the sorted and distinct `offsets` leave some residue free modulo every prime. Only the
+    /// primes up to the length of the pattern can cover all of their residues.
+    fn is_admissible(offsets: &[u32]) -> bool {
+        !offsets.is_empty()
+            && small_primes(offsets.len() as u32).into_iter().all(|q| {
+                let mut residues: Vec<u32> = offsets.iter().map(|o| o % q).collect();
+                residues.sort_unstable();
+                residues.dedup();
+                residues.len() < q as usize
+            })
+    }
+
+    /// Bounds for the `k`-th prime (1-indexed), from Dusart's inequalities
+    /// `p_k >= k (ln k + ln ln k - 1 + (ln ln k - 2.1) / ln k)` for `k >= 3` and
+    /// `p_k <= k (ln k + ln ln k)` for `k >= 6`.
+    fn nth_bounds(k: u64) -> (u32, u32) {
+        let (ln, k) = ((k as f64).ln(), k as f64);
+        let lower = iif!(k >= 3.0 => k * (ln + ln.ln() - 1.0 + (ln.ln() - 2.1) / ln); 0.0);
+        let upper = iif!(k >= 6.0 => k * (ln + ln.ln()); 11.0);
+        (lower as u32, upper.min(MAX_PRIME as f64) as u32)
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
+    use crate::is_factor;
+    use crate::prime_int::TRIAL_DIVISION_LIMIT;
+    use crate::util::sqrt;
 
     #[test]
     fn range_list_900_900() {
@@ -222,14 +466,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn range_list_matches_is_prime() {
+        (0..3).for_each(|i| {
+            let (b, e) = (MAX_PRIME - i * 400_000, MAX_PRIME - i * 300_000);
+            assert_eq!(
+                (b..=e)
+                    .filter(|&n| PrimeNumber::is_prime(n))
+                    .collect::<Vec<u32>>(),
+                PrimeNumber::range_list(b, e)
+            );
+        });
+    }
+
+    #[test]
+    fn range_list_top() {
+        assert_eq!(
+            vec![4294967279, 4294967291],
+            PrimeNumber::range_list(4294967270, u32::MAX)
+        );
+    }
+
     #[test]
     fn nth_0() {
         assert_eq!(Some(2), PrimeNumber::nth(0));
     }
 
+    #[test]
+    fn nth_99999() {
+        assert_eq!(Some(1299709), PrimeNumber::nth(99999));
+    }
+
     #[test]
     fn nth_199999() {
-        assert_eq!(Some(1299709), PrimeNumber::nth(199999));
+        assert_eq!(Some(2750159), PrimeNumber::nth(199999));
     }
 
     #[test]
@@ -237,6 +507,111 @@ mod tests {
         assert_eq!(Some(47), PrimeNumber::nth(14));
     }
 
+    #[test]
+    fn nth_matches_range_list() {
+        PrimeNumber::range_list(0, 10_000)
+            .into_iter()
+            .zip(0..)
+            .for_each(|(prime, n)| assert_eq!(Some(prime), PrimeNumber::nth(n)));
+    }
+
+    #[test]
+    fn nth_9999999() {
+        assert_eq!(Some(179424673), PrimeNumber::nth(9999999));
+    }
+
+    #[test]
+    fn nth_99999999() {
+        assert_eq!(Some(2038074743), PrimeNumber::nth(99999999));
+    }
+
+    #[test]
+    fn nth_max() {
+        assert_eq!(Some(MAX_PRIME), PrimeNumber::nth(MAX_PRIME_COUNT - 1));
+    }
+
+    #[test]
+    fn nth_max_plus_1() {
+        assert_eq!(None, PrimeNumber::nth(MAX_PRIME_COUNT));
+    }
+
+    #[test]
+    fn nth_u32_max() {
+        assert_eq!(None, PrimeNumber::nth(u32::MAX));
+    }
+
+    #[test]
+    fn count_0() {
+        assert_eq!(0, PrimeNumber::count(0));
+    }
+
+    #[test]
+    fn count_2() {
+        assert_eq!(1, PrimeNumber::count(2));
+    }
+
+    #[test]
+    fn count_100() {
+        assert_eq!(25, PrimeNumber::count(100));
+    }
+
+    #[test]
+    fn count_1_000_000_000() {
+        assert_eq!(50847534, PrimeNumber::count(1_000_000_000));
+    }
+
+    #[test]
+    fn count_max() {
+        assert_eq!(MAX_PRIME_COUNT, PrimeNumber::count(MAX_PRIME));
+    }
+
+    #[test]
+    fn count_u32_max() {
+        assert_eq!(MAX_PRIME_COUNT, PrimeNumber::count(u32::MAX));
+    }
+
+    #[test]
+    fn count_range_900_999() {
+        assert_eq!(14, PrimeNumber::count_range(900, 999));
+    }
+
+    #[test]
+    fn count_range_953_953() {
+        assert_eq!(1, PrimeNumber::count_range(953, 953));
+    }
+
+    #[test]
+    fn count_range_999_900() {
+        assert_eq!(0, PrimeNumber::count_range(999, 900));
+    }
+
+    #[test]
+    fn count_range_0_2() {
+        assert_eq!(1, PrimeNumber::count_range(0, 2));
+    }
+
+    #[test]
+    fn count_range_matches_range_list() {
+        let (b, e) = (MAX_PRIME - 1_000_000, MAX_PRIME);
+        assert_eq!(
+            PrimeNumber::range_list(b, e).len() as u32,
+            PrimeNumber::count_range(b, e)
+        );
+    }
+
+    #[test]
+    fn try_nth_0() {
+        assert_eq!(Ok(2), PrimeNumber::try_nth(0));
+    }
+
+    #[test]
+    fn try_nth_max_plus_1() {
+        assert_eq!(
+            Err(PrimeError::Overflow),
+            PrimeNumber::try_nth(MAX_PRIME_COUNT)
+        );
+    }
+
     #[test]
     fn random_range_953_999() {
         (0..9).for_each(|_| {
@@ -271,6 +646,96 @@ mod tests {
         assert!(PrimeNumber::random_range(953, 953).is_some());
     }
 
+    #[test]
+    fn random_range_0_1() {
+        (0..9).for_each(|_| {
+            assert!(PrimeNumber::random_range(0, 1).is_none());
+        });
+    }
+
+    #[test]
+    fn random_range_top() {
+        (0..9).for_each(|_| {
+            assert_eq!(
+                Some(MAX_PRIME),
+                PrimeNumber::random_range(MAX_PRIME - 1, u32::MAX)
+            );
+        });
+    }
+
+    #[test]
+    fn try_random_range_976_972() {
+        assert_eq!(
+            Err(PrimeError::InvalidRange),
+            PrimeNumber::try_random_range(976, 972)
+        );
+    }
+
+    #[test]
+    fn try_random_range_972_976() {
+        assert_eq!(
+            Err(PrimeError::NotFound),
+            PrimeNumber::try_random_range(972, 976)
+        );
+    }
+
+    #[test]
+    fn try_random_range_900_900() {
+        assert_eq!(
+            Err(PrimeError::NotFound),
+            PrimeNumber::try_random_range(900, 900)
+        );
+    }
+
+    #[test]
+    fn try_random_range_953_953() {
+        assert_eq!(Ok(953), PrimeNumber::try_random_range(953, 953));
+    }
+
+    #[test]
+    fn try_random_range_uniform_976_972() {
+        assert_eq!(
+            Err(PrimeError::InvalidRange),
+            PrimeNumber::try_random_range_uniform(976, 972)
+        );
+    }
+
+    #[test]
+    fn try_random_range_uniform_972_976() {
+        assert_eq!(
+            Err(PrimeError::NotFound),
+            PrimeNumber::try_random_range_uniform(972, 976)
+        );
+    }
+
+    #[test]
+    fn try_random_range_uniform_953_953() {
+        assert_eq!(Ok(953), PrimeNumber::try_random_range_uniform(953, 953));
+    }
+
+    #[test]
+    fn random_range_uniform_top() {
+        (0..99).for_each(|_| {
+            let p = PrimeNumber::random_range_uniform(u32::MAX - 20, u32::MAX).unwrap();
+            assert!(p == 4294967279 || p == MAX_PRIME);
+        });
+    }
+
+    #[test]
+    fn random_range_uniform_953_999() {
+        (0..99).for_each(|_| {
+            let p = PrimeNumber::random_range_uniform(953, 999).unwrap();
+            assert!((953..=999).contains(&p) && PrimeNumber::is_prime(p));
+        });
+    }
+
+    #[test]
+    fn try_random_ok() {
+        (0..99).for_each(|_| {
+            assert!(PrimeNumber::try_random().is_ok());
+        });
+    }
+
     #[test]
     fn random_ok() {
         (0..99).for_each(|_| {
@@ -288,6 +753,37 @@ mod tests {
         assert_eq!(Some(11), PrimeNumber::closest_prime(8, true));
     }
 
+    #[test]
+    fn closest_prime_0_asc() {
+        assert_eq!(Some(2), PrimeNumber::closest_prime(0, true));
+    }
+
+    #[test]
+    fn closest_prime_1_asc() {
+        assert_eq!(Some(2), PrimeNumber::closest_prime(1, true));
+    }
+
+    #[test]
+    fn try_closest_prime_1_desc() {
+        assert_eq!(
+            Err(PrimeError::NotFound),
+            PrimeNumber::try_closest_prime(1, false)
+        );
+    }
+
+    #[test]
+    fn try_closest_prime_max_plus_1_asc() {
+        assert_eq!(
+            Err(PrimeError::Overflow),
+            PrimeNumber::try_closest_prime(MAX_PRIME + 1, true)
+        );
+    }
+
+    #[test]
+    fn try_closest_prime_8_asc() {
+        assert_eq!(Ok(11), PrimeNumber::try_closest_prime(8, true));
+    }
+
     #[test]
     fn closest_prime_0_desc() {
         assert_eq!(None, PrimeNumber::closest_prime(0, false));
@@ -451,11 +947,57 @@ mod tests {
         assert_eq!(vec![7], PrimeNumber::prime_factor_list(7));
     }
 
+    #[test]
+    fn prime_factor_list_6() {
+        assert_eq!(vec![2, 3], PrimeNumber::prime_factor_list(6));
+    }
+
     #[test]
     fn prime_factor_list_210() {
         assert_eq!(vec![2, 3, 5, 7], PrimeNumber::prime_factor_list(210));
     }
 
+    #[test]
+    fn factorize_0() {
+        assert_eq!(None, PrimeNumber::factorize(0));
+    }
+
+    #[test]
+    fn factorize_1() {
+        assert_eq!(Some(Factorization::default()), PrimeNumber::factorize(1));
+    }
+
+    #[test]
+    fn factorize_12() {
+        assert_eq!(
+            Some(Factorization::new(vec![(2, 2), (3, 1)])),
+            PrimeNumber::factorize(12)
+        );
+    }
+
+    #[test]
+    fn factorize_2_pow_31() {
+        assert_eq!(
+            Some(Factorization::new(vec![(2, 31)])),
+            PrimeNumber::factorize(1 << 31)
+        );
+    }
+
+    #[test]
+    fn factorize_max() {
+        assert_eq!(
+            Some(Factorization::new(vec![(MAX_PRIME, 1)])),
+            PrimeNumber::factorize(MAX_PRIME)
+        );
+    }
+
+    #[test]
+    fn factorize_product() {
+        (1..10_000).chain(u32::MAX - 100..=u32::MAX).for_each(|n| {
+            assert_eq!(n, PrimeNumber::factorize(n).unwrap().product());
+        });
+    }
+
     #[test]
     fn prime_0() {
         assert_eq!(None, PrimeNumber::prime(0));
@@ -545,4 +1087,429 @@ mod tests {
     fn is_prime_max() {
         assert_eq!(true, PrimeNumber::is_prime(MAX_PRIME));
     }
+
+    #[test]
+    fn is_prime_2047() {
+        assert!(!PrimeNumber::is_prime(2047));
+    }
+
+    #[test]
+    fn is_prime_65537() {
+        assert!(PrimeNumber::is_prime(65537));
+    }
+
+    #[test]
+    fn is_prime_25326001() {
+        assert!(!PrimeNumber::is_prime(25326001));
+    }
+
+    #[test]
+    fn is_prime_3215031751() {
+        assert!(!PrimeNumber::is_prime(3215031751));
+    }
+
+    #[test]
+    fn is_prime_u32_max() {
+        assert!(!PrimeNumber::is_prime(u32::MAX));
+    }
+
+    #[test]
+    fn is_prime_matches_trial_division() {
+        let trial_division = |n: u32| n > 1 && (2..=sqrt(n)).all(|d| !is_factor!(n, d));
+        (0..200000)
+            .chain(TRIAL_DIVISION_LIMIT - 1000..TRIAL_DIVISION_LIMIT + 1000)
+            .chain(u32::MAX - 2000..=u32::MAX)
+            .for_each(|n| assert_eq!(trial_division(n), PrimeNumber::is_prime(n), "{}", n));
+    }
+
+    #[test]
+    fn twin_primes_0_20() {
+        assert_eq!(
+            vec![(3, 5), (5, 7), (11, 13), (17, 19)],
+            PrimeNumber::twin_primes(0, 20)
+        );
+    }
+
+    #[test]
+    fn twin_primes_pair_across_end() {
+        assert_eq!(vec![(3, 5)], PrimeNumber::twin_primes(3, 6));
+    }
+
+    #[test]
+    fn twin_primes_pair_across_begin() {
+        assert_eq!(vec![(11, 13)], PrimeNumber::twin_primes(6, 13));
+    }
+
+    #[test]
+    fn twin_primes_20_0() {
+        let empty: Vec<(u32, u32)> = Vec::new();
+        assert_eq!(empty, PrimeNumber::twin_primes(20, 0));
+    }
+
+    #[test]
+    fn twin_primes_1_000_000() {
+        assert_eq!(8169, PrimeNumber::twin_primes(0, 1_000_000).len());
+    }
+
+    #[test]
+    fn twin_primes_top() {
+        assert_eq!(
+            vec![(4294965671, 4294965673), (4294965839, 4294965841)],
+            PrimeNumber::twin_primes(4294965000, u32::MAX)
+        );
+    }
+
+    #[test]
+    fn cousin_primes_0_50() {
+        assert_eq!(
+            vec![(3, 7), (7, 11), (13, 17), (19, 23), (37, 41), (43, 47)],
+            PrimeNumber::cousin_primes(0, 50)
+        );
+    }
+
+    #[test]
+    fn cousin_primes_1_000_000() {
+        assert_eq!(8144, PrimeNumber::cousin_primes(0, 1_000_000).len());
+    }
+
+    #[test]
+    fn sexy_primes_0_30() {
+        assert_eq!(
+            vec![(5, 11), (7, 13), (11, 17), (13, 19), (17, 23), (23, 29)],
+            PrimeNumber::sexy_primes(0, 30)
+        );
+    }
+
+    #[test]
+    fn sexy_primes_1_000_000() {
+        assert_eq!(16386, PrimeNumber::sexy_primes(0, 1_000_000).len());
+    }
+
+    #[test]
+    fn sexy_primes_top() {
+        assert_eq!(
+            vec![(4294966651, 4294966657), (4294966661, 4294966667)],
+            PrimeNumber::sexy_primes(4294966600, u32::MAX)
+        );
+    }
+
+    #[test]
+    fn prime_pairs_gap_0() {
+        let empty: Vec<(u32, u32)> = Vec::new();
+        assert_eq!(empty, PrimeNumber::prime_pairs(0, 100, 0));
+    }
+
+    #[test]
+    fn prime_pairs_gap_1() {
+        assert_eq!(vec![(2, 3)], PrimeNumber::prime_pairs(0, 100, 1));
+    }
+
+    #[test]
+    fn prime_pairs_matches_is_prime() {
+        [2, 8, 10, 30].into_iter().for_each(|gap| {
+            let pairs: Vec<(u32, u32)> = (0..=5000 - gap)
+                .filter(|&p| PrimeNumber::is_prime(p) && PrimeNumber::is_prime(p + gap))
+                .map(|p| (p, p + gap))
+                .collect();
+            assert_eq!(pairs, PrimeNumber::prime_pairs(0, 5000, gap), "{}", gap);
+        });
+    }
+
+    #[test]
+    fn prime_pairs_flat_0_20() {
+        assert_eq!(
+            vec![3, 5, 5, 7, 11, 13, 17, 19],
+            PrimeNumber::twin_primes_flat(0, 20)
+        );
+    }
+
+    #[test]
+    fn is_twin_prime_small() {
+        let twins: Vec<u32> = (0..50).filter(|&n| PrimeNumber::is_twin_prime(n)).collect();
+        assert_eq!(vec![3, 5, 7, 11, 13, 17, 19, 29, 31, 41, 43], twins);
+    }
+
+    #[test]
+    fn is_twin_prime_max() {
+        assert!(!PrimeNumber::is_twin_prime(MAX_PRIME));
+        assert!(PrimeNumber::is_twin_prime(4294965841));
+    }
+
+    #[test]
+    fn is_cousin_prime_small() {
+        let cousins: Vec<u32> = (0..30)
+            .filter(|&n| PrimeNumber::is_cousin_prime(n))
+            .collect();
+        assert_eq!(vec![3, 7, 11, 13, 17, 19, 23], cousins);
+    }
+
+    #[test]
+    fn is_sexy_prime_small() {
+        let sexy: Vec<u32> = (0..30).filter(|&n| PrimeNumber::is_sexy_prime(n)).collect();
+        assert_eq!(vec![5, 7, 11, 13, 17, 19, 23, 29], sexy);
+    }
+
+    #[test]
+    fn prime_constellations_empty_pattern() {
+        assert_eq!(
+            Err(PrimeError::InvalidPattern),
+            PrimeNumber::prime_constellations(0, 100, &[])
+        );
+    }
+
+    #[test]
+    fn prime_constellations_inadmissible() {
+        [&[0, 1][..], &[0, 2, 4], &[0, 2, 6, 8, 12, 14]]
+            .into_iter()
+            .for_each(|pattern| {
+                assert_eq!(
+                    Err(PrimeError::InvalidPattern),
+                    PrimeNumber::prime_constellations(0, 100, pattern)
+                );
+            });
+    }
+
+    #[test]
+    fn prime_constellations_single() {
+        assert_eq!(
+            Ok(PrimeNumber::range_list(0, 100_000)),
+            PrimeNumber::prime_constellations(0, 100_000, &[0])
+        );
+    }
+
+    #[test]
+    fn prime_constellations_twins() {
+        let twins: Vec<u32> = PrimeNumber::twin_primes(0, 1_000_000)
+            .into_iter()
+            .map(|(p, _)| p)
+            .collect();
+        assert_eq!(
+            Ok(twins),
+            PrimeNumber::prime_constellations(0, 1_000_000, &[0, 2])
+        );
+    }
+
+    #[test]
+    fn prime_constellations_triplets_0_100() {
+        assert_eq!(
+            Ok(vec![5, 11, 17, 41]),
+            PrimeNumber::prime_constellations(0, 100, &[0, 2, 6])
+        );
+    }
+
+    #[test]
+    fn prime_constellations_quadruplets() {
+        assert_eq!(
+            Ok(vec![5, 11, 101, 191, 821, 1481, 1871, 2081, 3251, 3461]),
+            PrimeNumber::prime_constellations(0, 3470, &[0, 2, 6, 8])
+        );
+    }
+
+    #[test]
+    fn prime_constellations_unsorted_pattern() {
+        assert_eq!(
+            PrimeNumber::prime_constellations(0, 10_000, &[0, 4, 6]),
+            PrimeNumber::prime_constellations(0, 10_000, &[6, 0, 4, 4])
+        );
+    }
+
+    #[test]
+    fn prime_constellations_matches_is_prime() {
+        [
+            &[0, 2, 6][..],
+            &[0, 4, 6],
+            &[0, 4, 6, 10],
+            &[2, 6, 8, 12, 30],
+        ]
+        .into_iter()
+        .for_each(|pattern| {
+            let last = 20_000 - pattern.iter().max().unwrap();
+            let found: Vec<u32> = (100..=last)
+                .filter(|&n| pattern.iter().all(|&o| PrimeNumber::is_prime(n + o)))
+                .collect();
+            assert_eq!(
+                Ok(found),
+                PrimeNumber::prime_constellations(100, 20_000, pattern),
+                "{:?}",
+                pattern
+            );
+        });
+    }
+
+    #[test]
+    fn prime_constellations_members_in_range() {
+        assert_eq!(
+            Ok(vec![5]),
+            PrimeNumber::prime_constellations(0, 13, &[0, 2, 6, 8])
+        );
+    }
+
+    #[test]
+    fn prime_constellations_top() {
+        let found = PrimeNumber::prime_constellations(u32::MAX - 1_000_000, u32::MAX, &[0, 2, 6])
+            .unwrap_or_default();
+        assert!(!found.is_empty());
+        found.iter().for_each(|&n| {
+            assert!([0, 2, 6].iter().all(|&o| PrimeNumber::is_prime(n + o)));
+        });
+    }
+
+    #[test]
+    fn is_sophie_germain_small() {
+        let found: Vec<u32> = (0..100)
+            .filter(|&n| PrimeNumber::is_sophie_germain(n))
+            .collect();
+        assert_eq!(vec![2, 3, 5, 11, 23, 29, 41, 53, 83, 89], found);
+    }
+
+    #[test]
+    fn is_sophie_germain_max() {
+        assert!(PrimeNumber::is_sophie_germain(MAX_PRIME));
+    }
+
+    #[test]
+    fn is_safe_prime_small() {
+        let found: Vec<u32> = (0..100)
+            .filter(|&n| PrimeNumber::is_safe_prime(n))
+            .collect();
+        assert_eq!(vec![5, 7, 11, 23, 47, 59, 83], found);
+    }
+
+    #[test]
+    fn is_safe_prime_max() {
+        assert!(!PrimeNumber::is_safe_prime(MAX_PRIME));
+        assert!(PrimeNumber::is_safe_prime(4294967087));
+    }
+
+    #[test]
+    fn safe_primes_0_4() {
+        let empty: Vec<u32> = Vec::new();
+        assert_eq!(empty, PrimeNumber::safe_primes(0, 4));
+    }
+
+    #[test]
+    fn safe_primes_100_0() {
+        let empty: Vec<u32> = Vec::new();
+        assert_eq!(empty, PrimeNumber::safe_primes(100, 0));
+    }
+
+    #[test]
+    fn safe_primes_0_100() {
+        assert_eq!(
+            vec![5, 7, 11, 23, 47, 59, 83],
+            PrimeNumber::safe_primes(0, 100)
+        );
+    }
+
+    #[test]
+    fn safe_primes_bounds() {
+        assert_eq!(vec![23, 47], PrimeNumber::safe_primes(23, 47));
+        assert_eq!(vec![47], PrimeNumber::safe_primes(24, 58));
+    }
+
+    #[test]
+    fn safe_primes_1_000_000() {
+        assert_eq!(4324, PrimeNumber::safe_primes(0, 1_000_000).len());
+    }
+
+    #[test]
+    fn safe_primes_matches_is_safe_prime() {
+        let found: Vec<u32> = (u32::MAX - 20000..=u32::MAX)
+            .filter(|&n| PrimeNumber::is_safe_prime(n))
+            .collect();
+        assert_eq!(found, PrimeNumber::safe_primes(u32::MAX - 20000, u32::MAX));
+        assert_eq!(Some(&4294967087), found.last());
+    }
+
+    #[test]
+    fn random_safe_prime_invalid_range() {
+        assert_eq!(
+            Err(PrimeError::InvalidRange),
+            PrimeNumber::random_safe_prime(100, 99)
+        );
+    }
+
+    #[test]
+    fn random_safe_prime_not_found() {
+        assert_eq!(
+            Err(PrimeError::NotFound),
+            PrimeNumber::random_safe_prime(24, 46)
+        );
+    }
+
+    #[test]
+    fn random_safe_prime_range() {
+        (0..100).for_each(|_| {
+            let p = PrimeNumber::random_safe_prime(1000, 100_000).unwrap();
+            assert!((1000..=100_000).contains(&p));
+            assert!(PrimeNumber::is_safe_prime(p));
+        });
+    }
+
+    #[test]
+    fn random_safe_prime_single() {
+        assert_eq!(Ok(47), PrimeNumber::random_safe_prime(47, 47));
+    }
+
+    #[test]
+    fn cunningham_chain_not_prime() {
+        let empty: Vec<u64> = Vec::new();
+        assert_eq!(
+            empty,
+            PrimeNumber::cunningham_chain(9, CunninghamKind::First)
+        );
+    }
+
+    #[test]
+    fn cunningham_chain_0_1() {
+        let empty: Vec<u64> = Vec::new();
+        [0, 1].into_iter().for_each(|start| {
+            assert_eq!(
+                empty,
+                PrimeNumber::cunningham_chain(start, CunninghamKind::First)
+            );
+            assert_eq!(
+                empty,
+                PrimeNumber::cunningham_chain(start, CunninghamKind::Second)
+            );
+        });
+    }
+
+    #[test]
+    fn cunningham_chain_2() {
+        assert_eq!(
+            vec![2, 5, 11, 23, 47],
+            PrimeNumber::cunningham_chain(2, CunninghamKind::First)
+        );
+        assert_eq!(
+            vec![2, 3, 5],
+            PrimeNumber::cunningham_chain(2, CunninghamKind::Second)
+        );
+    }
+
+    #[test]
+    fn cunningham_chain_first_kind() {
+        assert_eq!(
+            vec![
+                1122659, 2245319, 4490639, 8981279, 17962559, 35925119, 71850239
+            ],
+            PrimeNumber::cunningham_chain(1122659, CunninghamKind::First)
+        );
+    }
+
+    #[test]
+    fn cunningham_chain_second_kind() {
+        assert_eq!(
+            vec![1531, 3061, 6121, 12241, 24481],
+            PrimeNumber::cunningham_chain(1531, CunninghamKind::Second)
+        );
+    }
+
+    #[test]
+    fn cunningham_chain_beyond_u32() {
+        assert_eq!(
+            vec![4294967291, 8589934583],
+            PrimeNumber::cunningham_chain(MAX_PRIME, CunninghamKind::First)
+        );
+    }
 }
@@ -0,0 +1,331 @@
+use std::cmp::Ordering::*;
+
+use rand::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use crate::iif;
+use crate::prime_int;
+use crate::sieve::prime_count;
+
+/// Largest bound accepted by the prime counting functions, above it time and memory grow too much.
+const MAX_COUNT: u64 = 1 << 40;
+
+#[wasm_bindgen]
+pub struct PrimeNumber64;
+
+#[wasm_bindgen]
+impl PrimeNumber64 {
+    /// See [`prime_int::range_list`].
+    #[wasm_bindgen(js_name = rangeList)]
+    pub fn range_list(begin: u64, end: u64) -> Vec<u64> {
+        prime_int::range_list(begin, end)
+    }
+
+    #[wasm_bindgen(js_name = primeCount)]
+    pub fn count(x: u64) -> Option<u64> {
+        iif!(x <= MAX_COUNT => Some(prime_count(x)); None)
+    }
+
+    #[wasm_bindgen(js_name = primeCountRange)]
+    pub fn count_range(begin: u64, end: u64) -> Option<u64> {
+        match (begin, end) {
+            (b, e) if b > e => Some(0),
+            (0, e) => Self::count(e),
+            (b, e) => Some(Self::count(e)? - Self::count(b - 1)?),
+        }
+    }
+
+    #[wasm_bindgen(js_name = randomRange)]
+    pub fn random_range(begin: u64, end: u64) -> Option<u64> {
+        match begin.cmp(&end) {
+            Greater => None,
+            Equal => Self::prime(begin),
+            _ => {
+                let orig = rand::rng().random_range(begin..=end);
+                if Self::is_prime(orig) {
+                    return Some(orig);
+                }
+                let above = orig
+                    .checked_add(1)
+                    .and_then(|n| Self::closest_prime(n, true))
+                    .filter(|&n| n <= end);
+                iif! {
+                    above;
+                    orig.checked_sub(1)
+                        .and_then(|n| Self::closest_prime(n, false))
+                        .filter(|&n| n >= begin)
+                }
+            }
+        }
+    }
+
+    /// See [`prime_int::try_closest_prime`].
+    #[wasm_bindgen(js_name = closestPrime)]
+    pub fn closest_prime(num: u64, asc: bool) -> Option<u64> {
+        prime_int::try_closest_prime(num, asc).ok()
+    }
+
+    /// See [`prime_int::prime_factor_list`].
+    #[wasm_bindgen(js_name = primeFactorList)]
+    pub fn prime_factor_list(num: u64) -> Vec<u64> {
+        prime_int::prime_factor_list(num)
+    }
+
+    #[wasm_bindgen]
+    pub fn prime(num: u64) -> Option<u64> {
+        iif!(Self::is_prime(num) => Some(num); None)
+    }
+
+    /// See [`prime_int::is_prime`].
+    #[wasm_bindgen(js_name = isPrime)]
+    pub fn is_prime(num: u64) -> bool {
+        prime_int::is_prime(num)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_PRIME: u64 = 18446744073709551557;
+
+    #[test]
+    fn range_list_900_900() {
+        let empty: Vec<u64> = Vec::new();
+        assert_eq!(empty, PrimeNumber64::range_list(900, 900));
+    }
+
+    #[test]
+    fn range_list_999_900() {
+        let empty: Vec<u64> = Vec::new();
+        assert_eq!(empty, PrimeNumber64::range_list(999, 900));
+    }
+
+    #[test]
+    fn range_list_900_953() {
+        assert_eq!(
+            vec![907, 911, 919, 929, 937, 941, 947, 953],
+            PrimeNumber64::range_list(900, 953)
+        );
+    }
+
+    #[test]
+    fn range_list_above_u32() {
+        assert_eq!(
+            vec![4294967311, 4294967357, 4294967371],
+            PrimeNumber64::range_list(4294967296, 4294967372)
+        );
+    }
+
+    #[test]
+    fn range_list_max() {
+        assert_eq!(
+            vec![MAX_PRIME],
+            PrimeNumber64::range_list(MAX_PRIME - 20, u64::MAX)
+        );
+    }
+
+    #[test]
+    fn count_100() {
+        assert_eq!(Some(25), PrimeNumber64::count(100));
+    }
+
+    #[test]
+    fn count_10_pow_10() {
+        assert_eq!(Some(455052511), PrimeNumber64::count(10_000_000_000));
+    }
+
+    #[test]
+    fn count_above_max() {
+        assert_eq!(None, PrimeNumber64::count(MAX_COUNT + 1));
+    }
+
+    #[test]
+    fn count_range_above_u32() {
+        assert_eq!(Some(3), PrimeNumber64::count_range(4294967296, 4294967372));
+    }
+
+    #[test]
+    fn count_range_999_900() {
+        assert_eq!(Some(0), PrimeNumber64::count_range(999, 900));
+    }
+
+    #[test]
+    fn count_range_above_max() {
+        assert_eq!(None, PrimeNumber64::count_range(0, u64::MAX));
+    }
+
+    #[test]
+    fn random_range_0_999() {
+        (0..9).for_each(|_| {
+            assert!(PrimeNumber64::random_range(0, 999).is_some());
+        });
+    }
+
+    #[test]
+    fn random_range_above_u32() {
+        (0..9).for_each(|_| {
+            let prime = PrimeNumber64::random_range(1 << 40, 1 << 41).unwrap();
+            assert!((1 << 40..=1 << 41).contains(&prime));
+            assert!(PrimeNumber64::is_prime(prime));
+        });
+    }
+
+    #[test]
+    fn random_range_max() {
+        (0..9).for_each(|_| {
+            assert_eq!(
+                Some(MAX_PRIME),
+                PrimeNumber64::random_range(MAX_PRIME - 1, u64::MAX)
+            );
+        });
+    }
+
+    #[test]
+    fn random_range_972_976() {
+        assert!(PrimeNumber64::random_range(972, 976).is_none());
+    }
+
+    #[test]
+    fn random_range_976_972() {
+        assert!(PrimeNumber64::random_range(976, 972).is_none());
+    }
+
+    #[test]
+    fn closest_prime_0_asc() {
+        assert_eq!(Some(2), PrimeNumber64::closest_prime(0, true));
+        assert_eq!(Some(2), PrimeNumber64::closest_prime(1, true));
+    }
+
+    #[test]
+    fn closest_prime_8_asc() {
+        assert_eq!(Some(11), PrimeNumber64::closest_prime(8, true));
+    }
+
+    #[test]
+    fn closest_prime_1_desc() {
+        assert_eq!(None, PrimeNumber64::closest_prime(1, false));
+    }
+
+    #[test]
+    fn closest_prime_u32_max_asc() {
+        assert_eq!(
+            Some(4294967311),
+            PrimeNumber64::closest_prime(u32::MAX as u64, true)
+        );
+    }
+
+    #[test]
+    fn closest_prime_u32_max_desc() {
+        assert_eq!(
+            Some(4294967291),
+            PrimeNumber64::closest_prime(u32::MAX as u64, false)
+        );
+    }
+
+    #[test]
+    fn closest_prime_max_plus_1_desc() {
+        assert_eq!(
+            Some(MAX_PRIME),
+            PrimeNumber64::closest_prime(MAX_PRIME + 1, false)
+        );
+    }
+
+    #[test]
+    fn closest_prime_max_plus_1_asc() {
+        assert_eq!(None, PrimeNumber64::closest_prime(MAX_PRIME + 1, true));
+    }
+
+    #[test]
+    fn prime_factor_list_0() {
+        let empty: Vec<u64> = Vec::new();
+        assert_eq!(empty, PrimeNumber64::prime_factor_list(0));
+    }
+
+    #[test]
+    fn prime_factor_list_1() {
+        let empty: Vec<u64> = Vec::new();
+        assert_eq!(empty, PrimeNumber64::prime_factor_list(1));
+    }
+
+    #[test]
+    fn prime_factor_list_210() {
+        assert_eq!(vec![2, 3, 5, 7], PrimeNumber64::prime_factor_list(210));
+    }
+
+    #[test]
+    fn prime_factor_list_2_pow_63() {
+        assert_eq!(vec![2], PrimeNumber64::prime_factor_list(1 << 63));
+    }
+
+    #[test]
+    fn prime_factor_list_u64_max() {
+        assert_eq!(
+            vec![3, 5, 17, 257, 641, 65537, 6700417],
+            PrimeNumber64::prime_factor_list(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn prime_factor_list_semiprime() {
+        assert_eq!(
+            vec![2147483629, 8589934609],
+            PrimeNumber64::prime_factor_list(2147483629 * 8589934609)
+        );
+    }
+
+    #[test]
+    fn prime_factor_list_max() {
+        assert_eq!(vec![MAX_PRIME], PrimeNumber64::prime_factor_list(MAX_PRIME));
+    }
+
+    #[test]
+    fn prime_4() {
+        assert_eq!(None, PrimeNumber64::prime(4));
+    }
+
+    #[test]
+    fn prime_max() {
+        assert_eq!(Some(MAX_PRIME), PrimeNumber64::prime(MAX_PRIME));
+    }
+
+    #[test]
+    fn is_prime_0() {
+        assert!(!PrimeNumber64::is_prime(0));
+    }
+
+    #[test]
+    fn is_prime_2() {
+        assert!(PrimeNumber64::is_prime(2));
+    }
+
+    #[test]
+    fn is_prime_u32_max_prime() {
+        assert!(PrimeNumber64::is_prime(4294967291));
+    }
+
+    #[test]
+    fn is_prime_2_pow_32_plus_15() {
+        assert!(PrimeNumber64::is_prime(4294967311));
+    }
+
+    #[test]
+    fn is_prime_strong_pseudoprime() {
+        assert!(!PrimeNumber64::is_prime(3825123056546413051));
+    }
+
+    #[test]
+    fn is_prime_mersenne_61() {
+        assert!(PrimeNumber64::is_prime((1 << 61) - 1));
+    }
+
+    #[test]
+    fn is_prime_u64_max() {
+        assert!(!PrimeNumber64::is_prime(u64::MAX));
+    }
+
+    #[test]
+    fn is_prime_max() {
+        assert!(PrimeNumber64::is_prime(MAX_PRIME));
+    }
+}
@@ -0,0 +1,595 @@
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::iter;
+use std::ops::{Add, BitAnd, Div, Mul, Rem, Shr, Sub};
+
+use crate::error::PrimeError;
+use crate::factorization::factor_pairs;
+use crate::iif;
+use crate::sieve::Sieve;
+
+/// Below this bound trial division is cheaper than a Miller-Rabin round.
+pub(crate) const TRIAL_DIVISION_LIMIT: u32 = 1 << 16;
+
+/// The primes below 50. [`is_prime`] divides by those below 29, [`factor_pairs`] removes all of
+/// them before Pollard's rho, and those below 43 are the witnesses of the last bound of
+/// [`WITNESSES`].
+pub(crate) const SMALL_PRIMES: [u64; 15] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47];
+
+/// Witnesses that make Miller-Rabin deterministic below each bound, the last one from Sorenson and
+/// Webster's search for strong pseudoprimes.
+const WITNESSES: [(u128, &[u64]); 3] = [
+    (4_759_123_141, &[2, 7, 61]),
+    (1 << 64, &[2, 325, 9375, 28178, 450775, 9780504, 1795265022]),
+    (
+        3_317_044_064_679_887_385_961_981,
+        SMALL_PRIMES.split_at(13).0,
+    ),
+];
+
+/// Unsigned integer the generic prime functions of this module work on, implemented for `u8`
+/// through `u128` and `usize`.
+pub trait PrimeInt:
+    Copy
+    + Ord
+    + Hash
+    + Debug
+    + Display
+    + Default
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + Shr<u32, Output = Self>
+    + BitAnd<Output = Self>
+    + From<u8>
+{
+    const MAX: Self;
+
+    fn to_u128(self) -> u128;
+
+    /// The value of `num`, if it fits in the type.
+    fn from_u128(num: u128) -> Option<Self>;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    fn trailing_zeros(self) -> u32;
+
+    fn isqrt(self) -> Self;
+
+    /// `self * other mod modulus` for `self, other < modulus`, which must not overflow.
+    fn mul_mod(self, other: Self, modulus: Self) -> Self;
+
+    /// Whether `divisor` divides `self`, never for a zero `divisor` like [`crate::is_factor`].
+    fn has_factor(self, divisor: Self) -> bool {
+        divisor != Self::from(0) && self % divisor == Self::from(0)
+    }
+
+    /// `self + other mod modulus` for `self, other < modulus`.
+    fn add_mod(self, other: Self, modulus: Self) -> Self {
+        iif!(self >= modulus - other => self - (modulus - other); self + other)
+    }
+
+    /// `self - other mod modulus` for `self, other < modulus`.
+    fn sub_mod(self, other: Self, modulus: Self) -> Self {
+        iif!(self >= other => self - other; self + (modulus - other))
+    }
+
+    fn pow_mod(self, exp: Self, modulus: Self) -> Self {
+        let (zero, one) = (Self::from(0), Self::from(1));
+        let (mut base, mut exp, mut res) = (self % modulus, exp, one % modulus);
+        while exp > zero {
+            if exp & one == one {
+                res = res.mul_mod(base, modulus);
+            }
+            base = base.mul_mod(base, modulus);
+            exp = exp >> 1;
+        }
+        res
+    }
+
+    fn gcd(self, other: Self) -> Self {
+        let (mut a, mut b) = (self, other);
+        while b != Self::from(0) {
+            (a, b) = (b, a % b);
+        }
+        a
+    }
+}
+
+macro_rules! impl_prime_int {
+    ($($t:ty => $wide:ty),* $(,)?) => {$(
+        impl PrimeInt for $t {
+            const MAX: Self = <$t>::MAX;
+
+            fn to_u128(self) -> u128 {
+                self as u128
+            }
+
+            fn from_u128(num: u128) -> Option<Self> {
+                Self::try_from(num).ok()
+            }
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn trailing_zeros(self) -> u32 {
+                <$t>::trailing_zeros(self)
+            }
+
+            fn isqrt(self) -> Self {
+                <$t>::isqrt(self)
+            }
+
+            fn mul_mod(self, other: Self, modulus: Self) -> Self {
+                (self as $wide * other as $wide % modulus as $wide) as $t
+            }
+        }
+    )*};
+}
+
+impl_prime_int!(u8 => u32, u16 => u32, u32 => u64, u64 => u128, usize => u128);
+
+impl PrimeInt for u128 {
+    const MAX: Self = u128::MAX;
+
+    fn to_u128(self) -> u128 {
+        self
+    }
+
+    fn from_u128(num: u128) -> Option<Self> {
+        Some(num)
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        u128::checked_add(self, other)
+    }
+
+    fn trailing_zeros(self) -> u32 {
+        u128::trailing_zeros(self)
+    }
+
+    fn isqrt(self) -> Self {
+        u128::isqrt(self)
+    }
+
+    /// Native below `2^64`. Above it the 256-bit product is divided by the modulus with Knuth's
+    /// algorithm D on 64-bit digits, the modulus being shifted so that its top bit is set and the
+    /// remainder shifted back at the end.
+    fn mul_mod(self, other: Self, modulus: Self) -> Self {
+        if modulus <= u64::MAX as u128 {
+            return self * other % modulus;
+        }
+        let (high, low) = widening_mul(self, other);
+        let shift = modulus.leading_zeros();
+        let divisor = modulus << shift;
+        // The product is below modulus^2, so its shifted high half is already below the divisor.
+        let rem = iif!(shift == 0 => high; high << shift | low >> (128 - shift));
+        let low = low << shift;
+        let rem = rem_digit(rem, (low >> 64) as u64, divisor);
+        rem_digit(rem, low as u64, divisor) >> shift
+    }
+}
+
+/// `a * b` as its high and low 128 bits.
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    let (a1, a0, b1, b0) = (a >> 64, a as u64 as u128, b >> 64, b as u64 as u128);
+    let (low, cross1, cross2) = (a0 * b0, a0 * b1, a1 * b0);
+    let middle = (low >> 64) + (cross1 as u64 as u128) + (cross2 as u64 as u128);
+    (
+        a1 * b1 + (cross1 >> 64) + (cross2 >> 64) + (middle >> 64),
+        middle << 64 | low as u64 as u128,
+    )
+}
+
+/// `(rem * 2^64 + digit) mod divisor` for `rem < divisor` and `divisor >= 2^127`. The quotient digit
+/// estimated from the top digits is at most 2 too large, each excess adding the divisor back once.
+fn rem_digit(rem: u128, digit: u64, divisor: u128) -> u128 {
+    let (d1, d0) = ((divisor >> 64) as u64, divisor as u64);
+    let q = iif!((rem >> 64) as u64 >= d1 => u64::MAX; (rem / d1 as u128) as u64);
+    let product_low = q as u128 * d0 as u128;
+    let product_high = q as u128 * d1 as u128 + (product_low >> 64);
+    let (mut r0, borrow) = digit.overflowing_sub(product_low as u64);
+    let (mut r1, mut negative) = rem.overflowing_sub(product_high + borrow as u128);
+    while negative {
+        let (sum, carry) = r0.overflowing_add(d0);
+        let (high, overflow) = r1.overflowing_add(d1 as u128 + carry as u128);
+        (r0, r1, negative) = (sum, high, !overflow);
+    }
+    r1 << 64 | r0 as u128
+}
+
+/// Whether `num` is prime: trial division up to [`TRIAL_DIVISION_LIMIT`], then Miller-Rabin with
+/// [`WITNESSES`], deterministic below `3.3 * 10^24`, and the Baillie-PSW test above, which has no
+/// known counterexample.
+pub fn is_prime<T: PrimeInt>(num: T) -> bool {
+    let mut small = SMALL_PRIMES[..9].iter().map(|&p| T::from(p as u8));
+    if let Some(p) = small.find(|&p| num.has_factor(p)) {
+        return num == p;
+    }
+    match num.to_u128() {
+        0 | 1 => false,
+        n if n < 29 * 29 => true,
+        n if n < TRIAL_DIVISION_LIMIT as u128 => {
+            let (limit, six, two) = (num.isqrt(), T::from(6), T::from(2));
+            !iter::successors(Some(T::from(29)), |&i| Some(i + six))
+                .take_while(|&i| i <= limit)
+                .any(|i| num.has_factor(i) || num.has_factor(i + two))
+        }
+        n => match WITNESSES.iter().find(|&&(bound, _)| n < bound) {
+            Some((_, bases)) => bases
+                .iter()
+                .all(|&a| T::from_u128(a as u128).is_none_or(|a| strong_probable_prime(num, a))),
+            None => strong_probable_prime(num, T::from(2)) && strong_lucas_probable_prime(num),
+        },
+    }
+}
+
+/// See [`try_closest_prime`].
+pub fn closest_prime<T: PrimeInt>(num: T, asc: bool) -> Option<T> {
+    try_closest_prime(num, asc).ok()
+}
+
+/// `num` if it is prime, otherwise the closest prime above it when `asc` and below it otherwise.
+/// [`PrimeError::Overflow`] when no prime above fits in the type, [`PrimeError::NotFound`] when
+/// there is none below.
+pub fn try_closest_prime<T: PrimeInt>(num: T, asc: bool) -> Result<T, PrimeError> {
+    let (one, two, three) = (T::from(1), T::from(2), T::from(3));
+    if num <= two {
+        return iif!(asc || num == two => Ok(two); Err(PrimeError::NotFound));
+    }
+    // Unsigned maxima are odd, so an even `num` always has a successor.
+    let start = iif!(num.has_factor(two) => iif!(asc => num + one; num - one); num);
+    let next = |&n: &T| iif!(asc => n.checked_add(two); Some(n - two).filter(|_| n > three));
+    iter::successors(Some(start), next)
+        .find(|&n| is_prime(n))
+        .ok_or(iif!(asc => PrimeError::Overflow; PrimeError::NotFound))
+}
+
+/// Primes in `begin..=end`, sieved when `end` fits in a `u32` and tested one by one otherwise.
+pub fn range_list<T: PrimeInt>(begin: T, end: T) -> Vec<T> {
+    match (begin.to_u128(), end.to_u128()) {
+        (b, e) if b == e => iif!(is_prime(begin) => vec![begin]; Vec::new()),
+        (b, e) if b > e => Vec::new(),
+        (b, e) if e <= u32::MAX as u128 => Sieve::new(b as u32, e as u32)
+            .filter_map(|p| T::from_u128(p as u128))
+            .collect(),
+        _ => iter::successors(Some(begin), |&n| {
+            n.checked_add(T::from(1)).filter(|&n| n <= end)
+        })
+        .filter(|&n| is_prime(n))
+        .collect(),
+    }
+}
+
+/// Distinct prime factors of `num` in ascending order, none for `0` and `1`.
+pub fn prime_factor_list<T: PrimeInt>(num: T) -> Vec<T> {
+    factor_pairs(num).into_iter().map(|(p, _)| p).collect()
+}
+
+/// Every divisor of `num` in ascending order, built from its prime factorization. None for `0`.
+pub fn factor_list<T: PrimeInt>(num: T) -> Vec<T> {
+    let one = T::from(1);
+    let mut divisors = iif!(num == T::from(0) => Vec::new(); vec![one]);
+    factor_pairs(num).into_iter().for_each(|(p, e)| {
+        let count = divisors.len();
+        (1..=e).fold(one, |power, _| {
+            let power = power * p;
+            (0..count).for_each(|i| divisors.push(divisors[i] * power));
+            power
+        });
+    });
+    divisors.sort_unstable();
+    divisors
+}
+
+/// Miller-Rabin round: checks whether `num` (odd, greater than `base`) is a strong probable prime
+/// to `base`.
+fn strong_probable_prime<T: PrimeInt>(num: T, base: T) -> bool {
+    let (one, minus_one) = (T::from(1), num - T::from(1));
+    let twos = minus_one.trailing_zeros();
+    let mut x = base.pow_mod(minus_one >> twos, num);
+    if x == one || x == minus_one {
+        return true;
+    }
+    (1..twos).any(|_| {
+        x = x.mul_mod(x, num);
+        x == minus_one
+    })
+}
+
+/// Strong Lucas probable prime test on the odd `num` with Selfridge's parameters: `D` the first of
+/// `5, -7, 9, -11, ...` with `(D / num) = -1`, `P = 1` and `Q = (1 - D) / 4`.
+fn strong_lucas_probable_prime<T: PrimeInt>(num: T) -> bool {
+    let (zero, one) = (T::from(0), T::from(1));
+    let root = num.isqrt();
+    // The Jacobi symbol of every `D` is `1` modulo a square.
+    if root * root == num {
+        return false;
+    }
+    let residue = |magnitude: u128, negative: bool| {
+        let rest = T::from_u128(magnitude).unwrap_or(zero) % num;
+        iif!(negative && rest != zero => num - rest; rest)
+    };
+    // Skipping a `D` that `num` divides, which only happens when it is `|D|`.
+    let (magnitude, negative) = iter::successors(Some((5, false)), |&(d, neg)| Some((d + 2, !neg)))
+        .find(|&(d, neg)| residue(d, neg) != zero && jacobi(residue(d, neg), num) != 1)
+        .unwrap_or_default();
+    let d = residue(magnitude, negative);
+    if jacobi(d, num) == 0 {
+        return false;
+    }
+    let q =
+        iif!(negative => residue((magnitude + 1) / 4, false); residue((magnitude - 1) / 4, true));
+    // Halving modulo the odd `num`: `(x + num) / 2` for an odd `x`, without overflowing.
+    let half = |x: T| iif!(x & one == one => (x >> 1) + (num >> 1) + one; x >> 1);
+    let k = (num + one).to_u128() >> (num + one).trailing_zeros();
+    let (mut u, mut v, mut qk) = (one, one, q);
+    (0..127 - k.leading_zeros()).rev().for_each(|bit| {
+        u = u.mul_mod(v, num);
+        v = v.mul_mod(v, num).sub_mod(qk.add_mod(qk, num), num);
+        qk = qk.mul_mod(qk, num);
+        if k >> bit & 1 == 1 {
+            (u, v) = (
+                half(u.add_mod(v, num)),
+                half(d.mul_mod(u, num).add_mod(v, num)),
+            );
+            qk = qk.mul_mod(q, num);
+        }
+    });
+    if u == zero || v == zero {
+        return true;
+    }
+    (1..(num + one).trailing_zeros()).any(|_| {
+        v = v.mul_mod(v, num).sub_mod(qk.add_mod(qk, num), num);
+        qk = qk.mul_mod(qk, num);
+        v == zero
+    })
+}
+
+/// Jacobi symbol `(a / n)` for an odd `n`.
+fn jacobi<T: PrimeInt>(a: T, n: T) -> i8 {
+    let (zero, one, three) = (T::from(0), T::from(1), T::from(3));
+    let (mut a, mut n, mut res) = (a % n, n, 1);
+    while a != zero {
+        while a & one == zero {
+            a = a >> 1;
+            if matches!((n & T::from(7)).to_u128(), 3 | 5) {
+                res = -res;
+            }
+        }
+        (a, n) = (n, a);
+        if a & three == three && n & three == three {
+            res = -res;
+        }
+        a = a % n;
+    }
+    iif!(n == one => res; 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prime::PrimeNumber;
+    use crate::residue;
+
+    #[test]
+    fn is_prime_u8_matches_u32() {
+        (0..=u8::MAX).for_each(|n| assert_eq!(PrimeNumber::is_prime(n as u32), is_prime(n)));
+    }
+
+    #[test]
+    fn is_prime_u16_matches_u32() {
+        (0..=u16::MAX).for_each(|n| assert_eq!(PrimeNumber::is_prime(n as u32), is_prime(n)));
+    }
+
+    #[test]
+    fn is_prime_usize() {
+        assert!(is_prime(4294967291usize));
+        assert!(!is_prime(4294967295usize));
+    }
+
+    #[test]
+    fn is_prime_u64_max_prime() {
+        assert!(is_prime(18446744073709551557u64));
+        assert!(!is_prime(u64::MAX));
+    }
+
+    #[test]
+    fn is_prime_u128_above_u64() {
+        let found: Vec<u128> = (1..=51).filter(|&k| is_prime((1 << 64) + k)).collect();
+        assert_eq!(vec![13, 37, 51], found);
+    }
+
+    #[test]
+    fn is_prime_u128_mersenne() {
+        assert!(is_prime((1u128 << 127) - 1));
+        assert!(is_prime((1u128 << 89) - 1));
+        assert!(!is_prime((1u128 << 101) - 1));
+        assert!(!is_prime(u128::MAX));
+    }
+
+    #[test]
+    fn is_prime_u128_strong_pseudoprime() {
+        // Strong pseudoprime to the first 13 prime bases, only Baillie-PSW rejects it.
+        let psi_13: u128 = 3_317_044_064_679_887_385_961_981;
+        assert!(
+            SMALL_PRIMES[..13]
+                .iter()
+                .all(|&a| strong_probable_prime(psi_13, a as u128))
+        );
+        assert!(!is_prime(psi_13));
+    }
+
+    #[test]
+    fn is_prime_u128_square() {
+        let p = (1u128 << 61) - 1;
+        assert!(!is_prime(p * p));
+    }
+
+    #[test]
+    fn is_prime_u128_semiprime() {
+        let (p, q): (u128, u128) = (18446744073709551557, 18446744073709551533);
+        assert!(!is_prime(p * q));
+    }
+
+    #[test]
+    fn strong_lucas_probable_prime_small() {
+        let found: Vec<u32> = (3..200)
+            .step_by(2)
+            .filter(|&n| strong_lucas_probable_prime(n))
+            .collect();
+        assert_eq!(PrimeNumber::range_list(3, 200), found);
+    }
+
+    #[test]
+    fn strong_lucas_probable_prime_pseudoprimes() {
+        // The smallest strong Lucas pseudoprimes with Selfridge's parameters.
+        let found: Vec<u32> = (3..20000)
+            .step_by(2)
+            .filter(|&n| strong_lucas_probable_prime(n) && !PrimeNumber::is_prime(n))
+            .collect();
+        assert_eq!(vec![5459, 5777, 10877, 16109, 18971], found);
+    }
+
+    #[test]
+    fn jacobi_matches_residue() {
+        (1..60u32).step_by(2).for_each(|n| {
+            (0..n).for_each(|a| {
+                assert_eq!(residue::jacobi(a, n), Ok(jacobi(a, n)), "{}/{}", a, n);
+            });
+        });
+    }
+
+    #[test]
+    fn mul_mod_u128_large() {
+        let m = u128::MAX - 158;
+        assert_eq!(1, (m - 1).mul_mod(m - 1, m));
+        assert_eq!(m - 6, (m - 2).mul_mod(3, m));
+    }
+
+    #[test]
+    fn mul_mod_u128_matches_double_and_add() {
+        let double_and_add = |a: u128, b: u128, m: u128| {
+            (0..128).rev().fold(0, |res: u128, i| {
+                let res = res.add_mod(res, m);
+                iif!(b >> i & 1 == 1 => res.add_mod(a, m); res)
+            })
+        };
+        let moduli = [
+            1 << 64,
+            (1 << 64) + 1,
+            1 << 127,
+            (1 << 127) + 1,
+            u128::MAX - 158,
+        ];
+        moduli.into_iter().for_each(|m| {
+            let values = [
+                0,
+                1,
+                2,
+                m / 3,
+                m / 2,
+                m - 2,
+                m - 1,
+                0x1234_5678_9abc_def0_1234_5678 % m,
+            ];
+            values.into_iter().for_each(|a| {
+                values.into_iter().for_each(|b| {
+                    assert_eq!(
+                        double_and_add(a, b, m),
+                        a.mul_mod(b, m),
+                        "{} {} {}",
+                        a,
+                        b,
+                        m
+                    );
+                });
+            });
+        });
+    }
+
+    #[test]
+    fn closest_prime_u8() {
+        assert_eq!(Ok(251u8), try_closest_prime(250, true));
+        assert_eq!(Err(PrimeError::Overflow), try_closest_prime(252u8, true));
+        assert_eq!(Ok(251u8), try_closest_prime(u8::MAX, false));
+    }
+
+    #[test]
+    fn closest_prime_below_3() {
+        assert_eq!(Ok(2u16), try_closest_prime(0, true));
+        assert_eq!(Err(PrimeError::NotFound), try_closest_prime(1u16, false));
+        assert_eq!(Ok(2u16), try_closest_prime(2, false));
+        assert_eq!(Ok(3u16), try_closest_prime(4, false));
+    }
+
+    #[test]
+    fn closest_prime_u128() {
+        assert_eq!(Some(u128::MAX - 158), closest_prime(u128::MAX, false));
+        assert_eq!(None, closest_prime(u128::MAX - 157, true));
+        assert_eq!(Some((1 << 127) - 1), closest_prime(1u128 << 127, false));
+        assert_eq!(
+            Some(1267650600228229401496703205653),
+            closest_prime(1u128 << 100, true)
+        );
+    }
+
+    #[test]
+    fn range_list_u8() {
+        assert_eq!(
+            vec![223u8, 227, 229, 233, 239, 241, 251],
+            range_list(220, u8::MAX)
+        );
+    }
+
+    #[test]
+    fn range_list_u64_above_u32() {
+        assert_eq!(
+            vec![4294967311u64, 4294967357, 4294967371],
+            range_list(4294967291 + 1, 4294967371)
+        );
+    }
+
+    #[test]
+    fn range_list_u128_reversed() {
+        let empty: Vec<u128> = Vec::new();
+        assert_eq!(empty, range_list(1u128 << 100, 1 << 99));
+    }
+
+    #[test]
+    fn prime_factor_list_u128() {
+        assert_eq!(
+            vec![3, 5, 17, 257, 641, 65537, 274177, 6700417, 67280421310721],
+            prime_factor_list(u128::MAX)
+        );
+    }
+
+    #[test]
+    fn prime_factor_list_u8() {
+        assert_eq!(vec![2u8, 3, 7], prime_factor_list(252));
+    }
+
+    #[test]
+    fn factor_list_u16() {
+        assert_eq!(
+            vec![
+                1u16, 3, 5, 15, 17, 51, 85, 255, 257, 771, 1285, 3855, 4369, 13107, 21845, 65535
+            ],
+            factor_list(u16::MAX)
+        );
+    }
+
+    #[test]
+    fn factor_list_u128_matches_u32() {
+        (0..500u32).for_each(|n| {
+            let wide: Vec<u32> = factor_list(n as u128)
+                .into_iter()
+                .map(|d| d as u32)
+                .collect();
+            assert_eq!(factor_list(n), wide);
+        });
+    }
+}
@@ -0,0 +1,506 @@
+use wasm_bindgen::prelude::*;
+
+use crate::arithmetic::carmichael_lambda;
+use crate::error::PrimeError;
+use crate::factorization::factor_pairs;
+use crate::iif;
+use crate::is_factor;
+use crate::modular::{gcd, mod_inv64};
+use crate::prime::PrimeNumber;
+use crate::prime_int::PrimeInt;
+use crate::util::is_even;
+
+/// Legendre symbol `(a / p)` for an odd prime `p`.
+#[wasm_bindgen]
+pub fn legendre(a: u32, p: u32) -> Result<i8, PrimeError> {
+    match p {
+        p if !PrimeNumber::is_prime(p) => Err(PrimeError::NotPrime),
+        2 => Err(PrimeError::InvalidModulus),
+        p => jacobi(a, p),
+    }
+}
+
+/// Jacobi symbol `(a / n)` for an odd `n`.
+#[wasm_bindgen]
+pub fn jacobi(a: u32, n: u32) -> Result<i8, PrimeError> {
+    if is_even(n) {
+        return Err(PrimeError::InvalidModulus);
+    }
+    let (mut a, mut n, mut result) = (a % n, n, 1);
+    while a != 0 {
+        while is_even(a) {
+            a >>= 1;
+            if n & 7 == 3 || n & 7 == 5 {
+                result = -result;
+            }
+        }
+        (a, n) = (n, a);
+        if a & 3 == 3 && n & 3 == 3 {
+            result = -result;
+        }
+        a %= n;
+    }
+    Ok(iif!(n == 1 => result; 0))
+}
+
+/// Square roots of `a` modulo the prime `p` in ascending order, found with Tonelli-Shanks.
+#[wasm_bindgen(js_name = sqrtMod)]
+pub fn sqrt_mod(a: u32, p: u32) -> Result<Vec<u32>, PrimeError> {
+    if !PrimeNumber::is_prime(p) {
+        return Err(PrimeError::NotPrime);
+    }
+    match a % p {
+        a if a == 0 || p == 2 => Ok(vec![a]),
+        a if legendre(a, p)? != 1 => Err(PrimeError::NoSolution),
+        a => {
+            let root = tonelli_shanks(a, p);
+            Ok(iif!(root < p - root => vec![root, p - root]; vec![p - root, root]))
+        }
+    }
+}
+
+/// Square roots of `a` modulo `p^k` in ascending order, lifting the roots modulo `p` with Hensel's
+/// lemma. `a` must be coprime to `p` and `p^k` must fit in a `u32`.
+#[wasm_bindgen(js_name = sqrtModPrimePower)]
+pub fn sqrt_mod_prime_power(a: u32, p: u32, k: u32) -> Result<Vec<u32>, PrimeError> {
+    if !PrimeNumber::is_prime(p) {
+        return Err(PrimeError::NotPrime);
+    }
+    let modulus = (p as u64)
+        .checked_pow(k)
+        .filter(|&m| k > 0 && m <= u32::MAX as u64)
+        .ok_or(PrimeError::InvalidModulus)?;
+    if k == 1 {
+        return sqrt_mod(a, p);
+    }
+    if is_factor!(a, p) {
+        return Err(PrimeError::NotCoprime);
+    }
+    let a = a as u64 % modulus;
+    let mut roots = match p {
+        2 => lift_power_of_two(a, k, modulus)?,
+        p => {
+            let (p, mut root, mut power) = (p as u64, sqrt_mod(a as u32, p)?[0] as u64, p as u64);
+            (1..k).for_each(|_| {
+                power *= p;
+                let error = (root.mul_mod(root, power) + power - a % power) % power;
+                let inverse = mod_inv64(2 * root % power, power).unwrap_or_default();
+                root = (root + power - error.mul_mod(inverse, power)) % power;
+            });
+            vec![root, modulus - root]
+        }
+    };
+    roots.sort_unstable();
+    roots.dedup();
+    Ok(roots.into_iter().map(|root| root as u32).collect())
+}
+
+/// Smallest positive `k` with `a^k = 1 (mod n)`, found by dividing the Carmichael function of `n`
+/// by its prime factors for as long as the power stays `1`.
+#[wasm_bindgen(js_name = multiplicativeOrder)]
+pub fn multiplicative_order(a: u32, n: u32) -> Result<u32, PrimeError> {
+    match n {
+        0 => Err(PrimeError::InvalidModulus),
+        n if gcd(a, n) != 1 => Err(PrimeError::NotCoprime),
+        n => {
+            let mut order = carmichael_lambda(n);
+            factor_pairs(order as u64).into_iter().for_each(|(q, _)| {
+                let q = q as u32;
+                while is_factor!(order, q) && a.pow_mod(order / q, n) == 1 % n {
+                    order /= q;
+                }
+            });
+            Ok(order)
+        }
+    }
+}
+
+/// Smallest generator of the multiplicative group modulo the prime `p`.
+#[wasm_bindgen(js_name = primitiveRoot)]
+pub fn primitive_root(p: u32) -> Result<u32, PrimeError> {
+    if !PrimeNumber::is_prime(p) {
+        return Err(PrimeError::NotPrime);
+    }
+    let factors = factor_pairs(p as u64 - 1);
+    (1..p)
+        .find(|&g| generates(g, p, &factors))
+        .ok_or(PrimeError::NotFound)
+}
+
+#[wasm_bindgen(js_name = isPrimitiveRoot)]
+pub fn is_primitive_root(g: u32, p: u32) -> Result<bool, PrimeError> {
+    match PrimeNumber::is_prime(p) {
+        true => Ok(generates(g, p, &factor_pairs(p as u64 - 1))),
+        false => Err(PrimeError::NotPrime),
+    }
+}
+
+/// Every generator of the multiplicative group modulo the prime `p` in ascending order, that is the
+/// powers `g^k` of the smallest one with `k` coprime to `p - 1`. Takes `O(p)` time.
+#[wasm_bindgen(js_name = primitiveRoots)]
+pub fn primitive_roots(p: u32) -> Result<Vec<u32>, PrimeError> {
+    let root = primitive_root(p)?;
+    let mut power = 1;
+    let mut roots: Vec<u32> = (1..p)
+        .filter_map(|k| {
+            power = power.mul_mod(root, p);
+            iif!(gcd(k, p - 1) == 1 => Some(power); None)
+        })
+        .collect();
+    roots.sort_unstable();
+    Ok(roots)
+}
+
+/// Square roots of the odd `a` modulo `2^k`, with `k >= 2`.
+fn lift_power_of_two(a: u64, k: u32, modulus: u64) -> Result<Vec<u64>, PrimeError> {
+    match (k, a & 7) {
+        (2, 1 | 5) => Ok(vec![1, 3]),
+        (2, _) | (_, 3 | 5 | 7) => Err(PrimeError::NoSolution),
+        _ => {
+            let root = (3..k).fold(1u64, |root, i| {
+                let power = 1 << (i + 1);
+                iif!(root * root % power == a % power => root; root + (1 << (i - 1)))
+            });
+            let half = modulus >> 1;
+            Ok(vec![
+                root,
+                modulus - root,
+                (root + half) % modulus,
+                (modulus - root + half) % modulus,
+            ])
+        }
+    }
+}
+
+/// Square root of the quadratic residue `a` modulo the odd prime `p`.
+fn tonelli_shanks(a: u32, p: u32) -> u32 {
+    if p & 3 == 3 {
+        return a.pow_mod((p + 1) / 4, p);
+    }
+    let shift = (p - 1).trailing_zeros();
+    let odd = (p - 1) >> shift;
+    let non_residue = (2..p)
+        .find(|&z| z.pow_mod((p - 1) / 2, p) == p - 1)
+        .unwrap_or(2);
+    let (mut m, mut c) = (shift, non_residue.pow_mod(odd, p));
+    let (mut t, mut root) = (a.pow_mod(odd, p), a.pow_mod(odd / 2 + 1, p));
+    while t != 1 {
+        let (mut i, mut square) = (0, t);
+        while square != 1 {
+            square = square.mul_mod(square, p);
+            i += 1;
+        }
+        let b = c.pow_mod(1 << (m - i - 1), p);
+        (m, c) = (i, b.mul_mod(b, p));
+        (t, root) = (t.mul_mod(c, p), root.mul_mod(b, p));
+    }
+    root
+}
+
+/// Whether `g` has order `p - 1` modulo the prime `p`, given the prime factors of `p - 1`.
+fn generates(g: u32, p: u32, factors: &[(u64, u32)]) -> bool {
+    !is_factor!(g, p)
+        && factors
+            .iter()
+            .all(|&(q, _)| g.pow_mod((p - 1) / q as u32, p) != 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_roots(a: u32, modulus: u32) -> Vec<u32> {
+        (0..modulus)
+            .filter(|&x| x as u64 * x as u64 % modulus as u64 == a as u64 % modulus as u64)
+            .collect()
+    }
+
+    #[test]
+    fn legendre_not_prime() {
+        assert_eq!(Err(PrimeError::NotPrime), legendre(2, 9));
+    }
+
+    #[test]
+    fn legendre_2() {
+        assert_eq!(Err(PrimeError::InvalidModulus), legendre(1, 2));
+    }
+
+    #[test]
+    fn legendre_0_7() {
+        assert_eq!(Ok(0), legendre(14, 7));
+    }
+
+    #[test]
+    fn legendre_2_7() {
+        assert_eq!(Ok(1), legendre(2, 7));
+    }
+
+    #[test]
+    fn legendre_3_7() {
+        assert_eq!(Ok(-1), legendre(3, 7));
+    }
+
+    #[test]
+    fn legendre_matches_euler_criterion() {
+        [3, 5, 7, 11, 13, 97, 65537].into_iter().for_each(|p| {
+            (0..200).for_each(|a| {
+                let euler = match a.pow_mod((p - 1) / 2, p) {
+                    0 => 0,
+                    1 => 1,
+                    _ => -1,
+                };
+                assert_eq!(Ok(euler), legendre(a, p), "({} / {})", a, p);
+            });
+        });
+    }
+
+    #[test]
+    fn jacobi_even() {
+        assert_eq!(Err(PrimeError::InvalidModulus), jacobi(3, 10));
+    }
+
+    #[test]
+    fn jacobi_0() {
+        assert_eq!(Err(PrimeError::InvalidModulus), jacobi(3, 0));
+    }
+
+    #[test]
+    fn jacobi_1001_9907() {
+        assert_eq!(Ok(-1), jacobi(1001, 9907));
+    }
+
+    #[test]
+    fn jacobi_2_15() {
+        assert_eq!(Ok(1), jacobi(2, 15));
+    }
+
+    #[test]
+    fn jacobi_matches_legendre_product() {
+        (1..300).step_by(2).for_each(|n| {
+            (0..60).for_each(|a| {
+                let product = PrimeNumber::factorize(n)
+                    .unwrap()
+                    .factors()
+                    .iter()
+                    .map(|&(p, e)| legendre(a, p).unwrap().pow(e))
+                    .product::<i8>();
+                assert_eq!(Ok(product), jacobi(a, n), "({} / {})", a, n);
+            });
+        });
+    }
+
+    #[test]
+    fn sqrt_mod_not_prime() {
+        assert_eq!(Err(PrimeError::NotPrime), sqrt_mod(4, 15));
+    }
+
+    #[test]
+    fn sqrt_mod_no_solution() {
+        assert_eq!(Err(PrimeError::NoSolution), sqrt_mod(3, 7));
+    }
+
+    #[test]
+    fn sqrt_mod_0() {
+        assert_eq!(Ok(vec![0]), sqrt_mod(13, 13));
+    }
+
+    #[test]
+    fn sqrt_mod_2() {
+        assert_eq!(Ok(vec![1]), sqrt_mod(3, 2));
+    }
+
+    #[test]
+    fn sqrt_mod_10_13() {
+        assert_eq!(Ok(vec![6, 7]), sqrt_mod(10, 13));
+    }
+
+    #[test]
+    fn sqrt_mod_large() {
+        let p = 4294967291;
+        let roots = sqrt_mod(123456789 * 3 % p, p).unwrap_or_default();
+        roots
+            .iter()
+            .for_each(|&r| assert_eq!(123456789 * 3 % p, r.mul_mod(r, p)));
+    }
+
+    #[test]
+    fn sqrt_mod_large_two_adic() {
+        let p = 4293918721;
+        (1..2000).map(|x| x.mul_mod(x, p)).for_each(|a| {
+            let roots = sqrt_mod(a, p).unwrap_or_default();
+            assert_eq!(2, roots.len());
+            roots.iter().for_each(|&r| assert_eq!(a, r.mul_mod(r, p)));
+        });
+    }
+
+    #[test]
+    fn sqrt_mod_matches_brute_force() {
+        [2, 3, 5, 13, 17, 41, 97, 193, 257, 769]
+            .into_iter()
+            .for_each(|p| {
+                (0..p).for_each(|a| {
+                    let roots = brute_roots(a, p);
+                    match sqrt_mod(a, p) {
+                        Ok(found) => assert_eq!(roots, found, "sqrt({}) mod {}", a, p),
+                        Err(error) => {
+                            assert_eq!(PrimeError::NoSolution, error);
+                            assert!(roots.is_empty());
+                        }
+                    }
+                });
+            });
+    }
+
+    #[test]
+    fn sqrt_mod_prime_power_not_prime() {
+        assert_eq!(Err(PrimeError::NotPrime), sqrt_mod_prime_power(4, 4, 2));
+    }
+
+    #[test]
+    fn sqrt_mod_prime_power_overflow() {
+        assert_eq!(
+            Err(PrimeError::InvalidModulus),
+            sqrt_mod_prime_power(4, 3, 21)
+        );
+    }
+
+    #[test]
+    fn sqrt_mod_prime_power_k_0() {
+        assert_eq!(
+            Err(PrimeError::InvalidModulus),
+            sqrt_mod_prime_power(4, 3, 0)
+        );
+    }
+
+    #[test]
+    fn sqrt_mod_prime_power_not_coprime() {
+        assert_eq!(Err(PrimeError::NotCoprime), sqrt_mod_prime_power(9, 3, 3));
+    }
+
+    #[test]
+    fn sqrt_mod_prime_power_2_pow_32() {
+        let roots = sqrt_mod_prime_power(17, 2, 31).unwrap_or_default();
+        assert_eq!(4, roots.len());
+        roots
+            .iter()
+            .for_each(|&r| assert_eq!(17, r as u64 * r as u64 % (1 << 31)));
+    }
+
+    #[test]
+    fn sqrt_mod_prime_power_matches_brute_force() {
+        [(2u32, 2), (2, 3), (2, 6), (3, 4), (5, 3), (7, 3), (13, 2)]
+            .into_iter()
+            .for_each(|(p, k)| {
+                let modulus = p.pow(k);
+                (1..modulus).filter(|a| !is_factor!(a, p)).for_each(|a| {
+                    let roots = brute_roots(a, modulus);
+                    match sqrt_mod_prime_power(a, p, k) {
+                        Ok(found) => assert_eq!(roots, found, "sqrt({}) mod {}^{}", a, p, k),
+                        Err(error) => {
+                            assert_eq!(PrimeError::NoSolution, error);
+                            assert!(roots.is_empty());
+                        }
+                    }
+                });
+            });
+    }
+
+    #[test]
+    fn multiplicative_order_mod_0() {
+        assert_eq!(Err(PrimeError::InvalidModulus), multiplicative_order(3, 0));
+    }
+
+    #[test]
+    fn multiplicative_order_not_coprime() {
+        assert_eq!(Err(PrimeError::NotCoprime), multiplicative_order(6, 9));
+    }
+
+    #[test]
+    fn multiplicative_order_mod_1() {
+        assert_eq!(Ok(1), multiplicative_order(5, 1));
+    }
+
+    #[test]
+    fn multiplicative_order_2_7() {
+        assert_eq!(Ok(3), multiplicative_order(2, 7));
+    }
+
+    #[test]
+    fn multiplicative_order_large() {
+        assert_eq!(Ok(4294967290), multiplicative_order(2, 4294967291));
+        assert_eq!(Ok(500000003), multiplicative_order(3, 1000000007));
+    }
+
+    #[test]
+    fn multiplicative_order_matches_brute_force() {
+        (1..200).for_each(|n| {
+            (0..n).filter(|&a| gcd(a, n) == 1).for_each(|a| {
+                let order = (1..=n).find(|&k| a.pow_mod(k, n) == 1 % n);
+                assert_eq!(
+                    order,
+                    multiplicative_order(a, n).ok(),
+                    "ord({}) mod {}",
+                    a,
+                    n
+                );
+            });
+        });
+    }
+
+    #[test]
+    fn primitive_root_not_prime() {
+        assert_eq!(Err(PrimeError::NotPrime), primitive_root(15));
+    }
+
+    #[test]
+    fn primitive_root_small() {
+        assert_eq!(
+            vec![Ok(1), Ok(2), Ok(2), Ok(3), Ok(2), Ok(2)],
+            [2, 3, 5, 7, 11, 13].map(primitive_root)
+        );
+    }
+
+    #[test]
+    fn primitive_root_large() {
+        assert_eq!(Ok(3), primitive_root(65537));
+        assert_eq!(Ok(19), primitive_root(4293918721));
+        assert_eq!(Ok(2), primitive_root(4294967291));
+    }
+
+    #[test]
+    fn is_primitive_root_not_prime() {
+        assert_eq!(Err(PrimeError::NotPrime), is_primitive_root(2, 9));
+    }
+
+    #[test]
+    fn is_primitive_root_13() {
+        assert_eq!(Ok(true), is_primitive_root(6, 13));
+        assert_eq!(Ok(false), is_primitive_root(4, 13));
+        assert_eq!(Ok(false), is_primitive_root(13, 13));
+    }
+
+    #[test]
+    fn is_primitive_root_above_p() {
+        assert_eq!(Ok(true), is_primitive_root(15, 13));
+    }
+
+    #[test]
+    fn primitive_roots_2() {
+        assert_eq!(Ok(vec![1]), primitive_roots(2));
+    }
+
+    #[test]
+    fn primitive_roots_13() {
+        assert_eq!(Ok(vec![2, 6, 7, 11]), primitive_roots(13));
+    }
+
+    #[test]
+    fn primitive_roots_match_order() {
+        [3, 5, 7, 11, 41, 97, 257, 7919].into_iter().for_each(|p| {
+            let roots: Vec<u32> = (1..p)
+                .filter(|&g| multiplicative_order(g, p) == Ok(p - 1))
+                .collect();
+            assert_eq!(Ok(roots), primitive_roots(p), "{}", p);
+        });
+    }
+}
@@ -0,0 +1,361 @@
+use std::iter;
+
+use rand::prelude::*;
+use rand::rngs::ChaCha8Rng;
+use wasm_bindgen::prelude::*;
+
+use crate::bigint::BigUint;
+use crate::error::PrimeError;
+use crate::prime::PrimeNumber;
+use crate::prime64::PrimeNumber64;
+
+/// Largest bit length accepted by [`PrimeRng::random_prime_bits`].
+pub const MAX_PRIME_BITS: u32 = 2048;
+
+/// Miller-Rabin rounds run by [`PrimeRng::random_prime_bits`] unless told otherwise.
+pub const DEFAULT_ROUNDS: u32 = 40;
+
+/// Random primes drawn from a stream of its own, so that the same seed always yields the same
+/// primes. Seeds feed ChaCha8, whose output does not depend on the platform.
+#[wasm_bindgen]
+pub struct PrimeRng {
+    rng: Box<dyn Rng>,
+}
+
+impl PrimeRng {
+    /// Draws the primes from `rng` instead of a seeded stream.
+    pub fn from_rng(rng: impl Rng + 'static) -> Self {
+        Self { rng: Box::new(rng) }
+    }
+
+    /// Probable prime of exactly `bits` bits, with `rounds` Miller-Rabin rounds. Odd candidates with
+    /// the top bit set are drawn until one passes, so every prime is equally likely.
+    pub(crate) fn random_big_prime(&mut self, bits: u32, rounds: u32) -> BigUint {
+        loop {
+            let mut candidate = BigUint::random(self.rng.as_mut(), bits as u64);
+            candidate.set_bit(bits as u64 - 1);
+            candidate.set_bit(0);
+            if candidate.is_probable_prime(rounds, self.rng.as_mut()) {
+                return candidate;
+            }
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl PrimeRng {
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: u64) -> Self {
+        Self::from_rng(ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    /// See [`PrimeNumber::try_random`].
+    #[wasm_bindgen]
+    pub fn random(&mut self) -> Result<u32, PrimeError> {
+        PrimeNumber::random_with(self.rng.as_mut())
+    }
+
+    /// See [`PrimeNumber::try_random_range`].
+    #[wasm_bindgen(js_name = randomRange)]
+    pub fn random_range(&mut self, begin: u32, end: u32) -> Result<u32, PrimeError> {
+        PrimeNumber::random_range_with(self.rng.as_mut(), begin, end)
+    }
+
+    /// See [`PrimeNumber::try_random_range_uniform`].
+    #[wasm_bindgen(js_name = randomRangeUniform)]
+    pub fn random_range_uniform(&mut self, begin: u32, end: u32) -> Result<u32, PrimeError> {
+        PrimeNumber::random_range_uniform_with(self.rng.as_mut(), begin, end)
+    }
+
+    /// See [`PrimeNumber::random_safe_prime`].
+    #[wasm_bindgen(js_name = randomSafePrime)]
+    pub fn random_safe_prime(&mut self, begin: u32, end: u32) -> Result<u32, PrimeError> {
+        PrimeNumber::random_safe_prime_with(self.rng.as_mut(), begin, end)
+    }
+
+    /// Prime of exactly `bits` bits, that is in `2^(bits - 1)..2^bits`, every one equally likely.
+    #[wasm_bindgen(js_name = randomBits)]
+    pub fn random_bits(&mut self, bits: u32) -> Result<u32, PrimeError> {
+        match bits {
+            0 => Err(PrimeError::InvalidRange),
+            1..=32 => self.random_range_uniform(1 << (bits - 1), u32::MAX >> (32 - bits)),
+            _ => Err(PrimeError::Overflow),
+        }
+    }
+
+    /// Prime of exactly `bits` bits for up to 64 bits, the top bit being set, every one equally
+    /// likely. Above 32 bits odd candidates are drawn until one is prime.
+    #[wasm_bindgen(js_name = randomBits64)]
+    pub fn random_bits64(&mut self, bits: u32) -> Result<u64, PrimeError> {
+        match bits {
+            0..=32 => self.random_bits(bits).map(u64::from),
+            33..=64 => {
+                Ok(
+                    iter::repeat_with(|| self.rng.next_u64() >> (64 - bits) | 1 << (bits - 1) | 1)
+                        .find(|&n| PrimeNumber64::is_prime(n))
+                        .unwrap_or_default(),
+                )
+            }
+            _ => Err(PrimeError::Overflow),
+        }
+    }
+
+    /// Prime of exactly `bits` bits for up to [`MAX_PRIME_BITS`] bits, in decimal since JS numbers
+    /// cannot hold it. Up to 64 bits the primality is certain, above that the prime is probable
+    /// after `rounds` Miller-Rabin rounds, [`DEFAULT_ROUNDS`] if omitted and at least one, a
+    /// composite passing them with probability below `4^-rounds`.
+    #[wasm_bindgen(js_name = randomPrimeBits)]
+    pub fn random_prime_bits(
+        &mut self,
+        bits: u32,
+        rounds: Option<u32>,
+    ) -> Result<String, PrimeError> {
+        match bits {
+            0..=64 => self.random_bits64(bits).map(|p| p.to_string()),
+            65..=MAX_PRIME_BITS => {
+                let rounds = rounds.unwrap_or(DEFAULT_ROUNDS).max(1);
+                Ok(self.random_big_prime(bits, rounds).to_string())
+            }
+            _ => Err(PrimeError::Overflow),
+        }
+    }
+
+    /// `count` primes drawn one after the other from `begin..=end`.
+    #[wasm_bindgen(js_name = randomBatch)]
+    pub fn random_batch(
+        &mut self,
+        count: u32,
+        begin: u32,
+        end: u32,
+    ) -> Result<Vec<u32>, PrimeError> {
+        (0..count).map(|_| self.random_range(begin, end)).collect()
+    }
+}
+
+/// [`PrimeRng::random_prime_bits`] drawing from the thread's generator instead of a seed.
+#[wasm_bindgen(js_name = randomPrimeBits)]
+pub fn random_prime_bits(bits: u32, rounds: Option<u32>) -> Result<String, PrimeError> {
+    PrimeRng::from_rng(rand::rng()).random_prime_bits(bits, rounds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn random_same_seed() {
+        let (mut first, mut second) = (PrimeRng::new(7), PrimeRng::new(7));
+        (0..20).for_each(|_| assert_eq!(first.random(), second.random()));
+    }
+
+    #[test]
+    fn random_different_seeds() {
+        let (mut first, mut second) = (PrimeRng::new(1), PrimeRng::new(2));
+        assert_ne!(
+            first.random_batch(8, 0, u32::MAX),
+            second.random_batch(8, 0, u32::MAX)
+        );
+    }
+
+    #[test]
+    fn random_is_prime() {
+        let mut rng = PrimeRng::new(0);
+        (0..100).for_each(|_| assert!(PrimeNumber::is_prime(rng.random().unwrap())));
+    }
+
+    #[test]
+    fn random_range_stream() {
+        assert_eq!(
+            Ok(vec![227, 683, 149, 953, 773]),
+            PrimeRng::new(42).random_batch(5, 0, 1000)
+        );
+    }
+
+    #[test]
+    fn random_range_976_972() {
+        assert_eq!(
+            Err(PrimeError::InvalidRange),
+            PrimeRng::new(0).random_range(976, 972)
+        );
+    }
+
+    #[test]
+    fn random_range_972_976() {
+        assert_eq!(
+            Err(PrimeError::NotFound),
+            PrimeRng::new(0).random_range(972, 976)
+        );
+    }
+
+    #[test]
+    fn random_range_in_range() {
+        let mut rng = PrimeRng::new(3);
+        (0..100).for_each(|_| {
+            let p = rng.random_range(1000, 2000).unwrap();
+            assert!((1000..=2000).contains(&p) && PrimeNumber::is_prime(p));
+        });
+    }
+
+    /// Pearson's chi-squared statistic of `samples` draws over the primes up to 200.
+    fn chi_squared(samples: u32, mut draw: impl FnMut() -> u32) -> f64 {
+        let primes = PrimeNumber::range_list(0, 200);
+        let mut counts = vec![0u32; primes.len()];
+        (0..samples).for_each(|_| counts[primes.binary_search(&draw()).unwrap()] += 1);
+        let expected = samples as f64 / primes.len() as f64;
+        counts
+            .iter()
+            .map(|&count| (count as f64 - expected).powi(2) / expected)
+            .sum()
+    }
+
+    #[test]
+    fn random_range_uniform_distribution() {
+        // 46 primes up to 200, so 45 degrees of freedom: 80.08 is the 0.999 quantile.
+        let mut rng = PrimeRng::new(11);
+        let statistic = chi_squared(46_000, || rng.random_range_uniform(0, 200).unwrap());
+        assert!(statistic < 80.08, "{}", statistic);
+    }
+
+    #[test]
+    fn random_range_distribution_is_biased() {
+        let mut rng = PrimeRng::new(11);
+        let statistic = chi_squared(46_000, || rng.random_range(0, 200).unwrap());
+        assert!(statistic > 80.08, "{}", statistic);
+    }
+
+    #[test]
+    fn random_range_uniform_972_976() {
+        assert_eq!(
+            Err(PrimeError::NotFound),
+            PrimeRng::new(0).random_range_uniform(972, 976)
+        );
+    }
+
+    #[test]
+    fn random_safe_prime_same_seed() {
+        let draw = |seed| {
+            let mut rng = PrimeRng::new(seed);
+            (0..20)
+                .map(|_| rng.random_safe_prime(1000, 100_000).unwrap())
+                .collect::<Vec<u32>>()
+        };
+        assert_eq!(draw(19), draw(19));
+        assert!(draw(19).iter().all(|&p| PrimeNumber::is_safe_prime(p)));
+    }
+
+    #[test]
+    fn random_bits_0() {
+        assert_eq!(
+            Err(PrimeError::InvalidRange),
+            PrimeRng::new(0).random_bits(0)
+        );
+    }
+
+    #[test]
+    fn random_bits_1() {
+        assert_eq!(Err(PrimeError::NotFound), PrimeRng::new(0).random_bits(1));
+    }
+
+    #[test]
+    fn random_bits_33() {
+        assert_eq!(Err(PrimeError::Overflow), PrimeRng::new(0).random_bits(33));
+    }
+
+    #[test]
+    fn random_bits_is_uniform() {
+        let (mut bits, mut uniform) = (PrimeRng::new(23), PrimeRng::new(23));
+        (0..20).for_each(|_| {
+            assert_eq!(uniform.random_range_uniform(512, 1023), bits.random_bits(10));
+        });
+    }
+
+    #[test]
+    fn random_bits_length() {
+        let mut rng = PrimeRng::new(5);
+        (2..=32).for_each(|bits| {
+            let p = rng.random_bits(bits).unwrap();
+            assert_eq!(bits, 32 - p.leading_zeros(), "{}", p);
+            assert!(PrimeNumber::is_prime(p));
+        });
+    }
+
+    #[test]
+    fn random_batch_empty() {
+        assert_eq!(Ok(Vec::new()), PrimeRng::new(0).random_batch(0, 0, 100));
+    }
+
+    #[test]
+    fn random_batch_invalid_range() {
+        assert_eq!(
+            Err(PrimeError::InvalidRange),
+            PrimeRng::new(0).random_batch(3, 100, 0)
+        );
+    }
+
+    #[test]
+    fn from_rng_matches_seeded_rng() {
+        let mut first = PrimeRng::from_rng(StdRng::seed_from_u64(9));
+        let mut second = PrimeRng::from_rng(StdRng::seed_from_u64(9));
+        assert_eq!(
+            first.random_batch(10, 0, u32::MAX),
+            second.random_batch(10, 0, u32::MAX)
+        );
+    }
+
+    #[test]
+    fn random_bits64_length() {
+        let mut rng = PrimeRng::new(5);
+        (2..=64).for_each(|bits| {
+            let p = rng.random_bits64(bits).unwrap();
+            assert_eq!(bits, 64 - p.leading_zeros(), "{}", p);
+            assert!(PrimeNumber64::is_prime(p));
+        });
+    }
+
+    #[test]
+    fn random_bits64_65() {
+        assert_eq!(
+            Err(PrimeError::Overflow),
+            PrimeRng::new(0).random_bits64(65)
+        );
+    }
+
+    #[test]
+    fn random_prime_bits_out_of_range() {
+        let mut rng = PrimeRng::new(0);
+        assert_eq!(
+            Err(PrimeError::InvalidRange),
+            rng.random_prime_bits(0, None)
+        );
+        assert_eq!(Err(PrimeError::NotFound), rng.random_prime_bits(1, None));
+        assert_eq!(Err(PrimeError::Overflow), rng.random_prime_bits(2049, None));
+    }
+
+    #[test]
+    fn random_prime_bits_small_is_decimal() {
+        let mut rng = PrimeRng::new(8);
+        let p: u64 = rng.random_prime_bits(40, None).unwrap().parse().unwrap();
+        assert_eq!(40, 64 - p.leading_zeros());
+        assert!(PrimeNumber64::is_prime(p));
+    }
+
+    #[test]
+    fn random_prime_bits_same_seed() {
+        assert_eq!(
+            PrimeRng::new(4).random_prime_bits(256, Some(8)),
+            PrimeRng::new(4).random_prime_bits(256, Some(8))
+        );
+    }
+
+    #[test]
+    fn random_big_prime_length() {
+        let mut rng = PrimeRng::new(6);
+        let mut check = StdRng::seed_from_u64(6);
+        [65, 100, 128, 256, 512].into_iter().for_each(|bits| {
+            let p = rng.random_big_prime(bits, 10);
+            assert_eq!(bits as u64, p.bits());
+            assert!(p.is_probable_prime(20, &mut check));
+        });
+    }
+}
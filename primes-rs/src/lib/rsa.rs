@@ -0,0 +1,371 @@
+//! Textbook RSA on 64-bit moduli, for teaching how the scheme works.
+//!
+//! **Not for production.** The keys are small enough to factor in moments, messages are
+//! encrypted and signed as they are without any padding, and nothing runs in constant time.
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::PrimeError;
+use crate::iif;
+use crate::modular::{gcd64, lcm64, mod_inv64};
+use crate::prime_int::PrimeInt;
+use crate::prime64::PrimeNumber64;
+use crate::rng::PrimeRng;
+
+/// Public exponent of generated keys, the usual `2^16 + 1`.
+pub const DEFAULT_EXPONENT: u64 = 65537;
+
+/// Public half of a key: the modulus `n` and the exponent `e`.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RsaPublicKey {
+    n: u64,
+    e: u64,
+}
+
+/// Key pair made of two primes `p` and `q`, with the private exponent `d` and the CRT parameters
+/// `dp = d mod (p - 1)`, `dq = d mod (q - 1)` and `qinv = q^-1 mod p` which speed decryption up.
+/// `d` is the inverse of `e` modulo `lcm(p - 1, q - 1)`, which may be smaller than the inverse
+/// modulo `(p - 1)(q - 1)` that textbooks use, both decrypting the same.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RsaKeyPair {
+    p: u64,
+    q: u64,
+    n: u64,
+    e: u64,
+    d: u64,
+    dp: u64,
+    dq: u64,
+    qinv: u64,
+}
+
+#[wasm_bindgen]
+impl RsaPublicKey {
+    #[wasm_bindgen(constructor)]
+    pub fn new(n: u64, e: u64) -> Self {
+        Self { n, e }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn n(&self) -> u64 {
+        self.n
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn e(&self) -> u64 {
+        self.e
+    }
+
+    /// `m^e mod n`, the message being below `n`.
+    #[wasm_bindgen]
+    pub fn encrypt(&self, m: u64) -> Result<u64, PrimeError> {
+        iif!(m < self.n => Ok(m.pow_mod(self.e, self.n)); Err(PrimeError::InvalidRange))
+    }
+
+    /// Whether `s^e mod n` gives back the message `m`.
+    #[wasm_bindgen]
+    pub fn verify(&self, m: u64, s: u64) -> bool {
+        m < self.n && s < self.n && s.pow_mod(self.e, self.n) == m
+    }
+
+    /// Decimal strings, since JSON numbers lose precision above `2^53`: `{"n":"…","e":"…"}`.
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> String {
+        json(&[("n", self.n), ("e", self.e)])
+    }
+}
+
+#[wasm_bindgen]
+impl RsaKeyPair {
+    /// Key pair of the distinct odd primes `p` and `q` with the public exponent `e`, whose modulus
+    /// `pq` must fit in a `u64` and which `e > 1` must be coprime to `lcm(p - 1, q - 1)`.
+    #[wasm_bindgen(js_name = fromPrimes)]
+    pub fn from_primes(p: u64, q: u64, e: u64) -> Result<Self, PrimeError> {
+        if !PrimeNumber64::is_prime(p) || !PrimeNumber64::is_prime(q) {
+            return Err(PrimeError::NotPrime);
+        }
+        if p == q || p == 2 || q == 2 {
+            return Err(PrimeError::InvalidModulus);
+        }
+        if e < 2 {
+            return Err(PrimeError::InvalidRange);
+        }
+        let n = p.checked_mul(q).ok_or(PrimeError::Overflow)?;
+        let lambda = lcm64(p - 1, q - 1).ok_or(PrimeError::Overflow)?;
+        if gcd64(e, lambda) != 1 {
+            return Err(PrimeError::NotCoprime);
+        }
+        let d = mod_inv64(e % lambda, lambda).ok_or(PrimeError::NotCoprime)?;
+        let qinv = mod_inv64(q % p, p).ok_or(PrimeError::NotCoprime)?;
+        Ok(Self {
+            p,
+            q,
+            n,
+            e,
+            d,
+            dp: d % (p - 1),
+            dq: d % (q - 1),
+            qinv,
+        })
+    }
+
+    /// Key pair of two distinct 32-bit primes drawn with [`PrimeRng::random_range`], so with a 63 or
+    /// 64-bit modulus, and the exponent [`DEFAULT_EXPONENT`].
+    #[wasm_bindgen]
+    pub fn generate(rng: &mut PrimeRng) -> Result<Self, PrimeError> {
+        loop {
+            let (p, q) = (
+                rng.random_range(1 << 31, u32::MAX)?,
+                rng.random_range(1 << 31, u32::MAX)?,
+            );
+            match Self::from_primes(p as u64, q as u64, DEFAULT_EXPONENT) {
+                Err(PrimeError::InvalidModulus | PrimeError::NotCoprime) => continue,
+                key => return key,
+            }
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn p(&self) -> u64 {
+        self.p
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn q(&self) -> u64 {
+        self.q
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn n(&self) -> u64 {
+        self.n
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn e(&self) -> u64 {
+        self.e
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn d(&self) -> u64 {
+        self.d
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn dp(&self) -> u64 {
+        self.dp
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn dq(&self) -> u64 {
+        self.dq
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn qinv(&self) -> u64 {
+        self.qinv
+    }
+
+    #[wasm_bindgen(js_name = publicKey)]
+    pub fn public_key(&self) -> RsaPublicKey {
+        RsaPublicKey::new(self.n, self.e)
+    }
+
+    /// See [`RsaPublicKey::encrypt`].
+    #[wasm_bindgen]
+    pub fn encrypt(&self, m: u64) -> Result<u64, PrimeError> {
+        self.public_key().encrypt(m)
+    }
+
+    /// `c^d mod n` for a ciphertext below `n`, see [`RsaKeyPair::sign`].
+    #[wasm_bindgen]
+    pub fn decrypt(&self, c: u64) -> Result<u64, PrimeError> {
+        self.sign(c)
+    }
+
+    /// `m^d mod n` for a message below `n`, computed modulo `p` and `q` with the CRT parameters and
+    /// recombined with Garner's formula `m2 + q (qinv (m1 - m2) mod p)`.
+    #[wasm_bindgen]
+    pub fn sign(&self, m: u64) -> Result<u64, PrimeError> {
+        if m >= self.n {
+            return Err(PrimeError::InvalidRange);
+        }
+        let (m1, m2) = (m.pow_mod(self.dp, self.p), m.pow_mod(self.dq, self.q));
+        let h = self
+            .qinv
+            .mul_mod((m1 + self.p - m2 % self.p) % self.p, self.p);
+        Ok(m2 + h * self.q)
+    }
+
+    /// See [`RsaPublicKey::verify`].
+    #[wasm_bindgen]
+    pub fn verify(&self, m: u64, s: u64) -> bool {
+        self.public_key().verify(m, s)
+    }
+
+    /// Every parameter as a decimal string, since JSON numbers lose precision above `2^53`:
+    /// `{"p":"…","q":"…","n":"…","e":"…","d":"…","dp":"…","dq":"…","qinv":"…"}`.
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> String {
+        json(&[
+            ("p", self.p),
+            ("q", self.q),
+            ("n", self.n),
+            ("e", self.e),
+            ("d", self.d),
+            ("dp", self.dp),
+            ("dq", self.dq),
+            ("qinv", self.qinv),
+        ])
+    }
+}
+
+/// JSON object of the `fields`, their values as strings.
+fn json(fields: &[(&str, u64)]) -> String {
+    let members: Vec<String> = fields
+        .iter()
+        .map(|(name, value)| format!("\"{}\":\"{}\"", name, value))
+        .collect();
+    format!("{{{}}}", members.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn textbook() -> RsaKeyPair {
+        RsaKeyPair::from_primes(61, 53, 17).unwrap()
+    }
+
+    #[test]
+    fn from_primes_textbook() {
+        let key = textbook();
+        assert_eq!(
+            (3233, 17, 413, 53, 49, 38),
+            (key.n(), key.e(), key.d(), key.dp(), key.dq(), key.qinv())
+        );
+    }
+
+    #[test]
+    fn from_primes_not_prime() {
+        assert_eq!(
+            Err(PrimeError::NotPrime),
+            RsaKeyPair::from_primes(61, 51, 17)
+        );
+    }
+
+    #[test]
+    fn from_primes_equal() {
+        assert_eq!(
+            Err(PrimeError::InvalidModulus),
+            RsaKeyPair::from_primes(61, 61, 17)
+        );
+    }
+
+    #[test]
+    fn from_primes_even() {
+        assert_eq!(
+            Err(PrimeError::InvalidModulus),
+            RsaKeyPair::from_primes(2, 3, 5)
+        );
+        assert_eq!(
+            Err(PrimeError::InvalidModulus),
+            RsaKeyPair::from_primes(61, 2, 17)
+        );
+    }
+
+    #[test]
+    fn from_primes_exponent_1() {
+        assert_eq!(
+            Err(PrimeError::InvalidRange),
+            RsaKeyPair::from_primes(61, 53, 1)
+        );
+    }
+
+    #[test]
+    fn from_primes_not_coprime() {
+        assert_eq!(
+            Err(PrimeError::NotCoprime),
+            RsaKeyPair::from_primes(61, 53, 15)
+        );
+    }
+
+    #[test]
+    fn from_primes_overflow() {
+        assert_eq!(
+            Err(PrimeError::Overflow),
+            RsaKeyPair::from_primes(18446744073709551557, 3, 5)
+        );
+    }
+
+    #[test]
+    fn encrypt_textbook() {
+        assert_eq!(Ok(2790), textbook().encrypt(65));
+        assert_eq!(Ok(65), textbook().decrypt(2790));
+    }
+
+    #[test]
+    fn encrypt_out_of_range() {
+        assert_eq!(Err(PrimeError::InvalidRange), textbook().encrypt(3233));
+        assert_eq!(Err(PrimeError::InvalidRange), textbook().decrypt(3233));
+    }
+
+    #[test]
+    fn decrypt_every_message() {
+        let key = textbook();
+        (0..key.n()).for_each(|m| {
+            let c = key.encrypt(m).unwrap();
+            assert_eq!(c.pow_mod(key.d(), key.n()), key.decrypt(c).unwrap());
+            assert_eq!(Ok(m), key.decrypt(c));
+        });
+    }
+
+    #[test]
+    fn sign_verify() {
+        let key = textbook();
+        let s = key.sign(123).unwrap();
+        assert_eq!(123u64.pow_mod(413, 3233), s);
+        assert!(key.verify(123, s));
+        assert!(key.public_key().verify(123, s));
+        assert!(!key.verify(124, s));
+        assert!(!key.verify(123, s + key.n()));
+    }
+
+    #[test]
+    fn generate_round_trip() {
+        let mut rng = PrimeRng::new(24);
+        (0..20).for_each(|_| {
+            let key = RsaKeyPair::generate(&mut rng).unwrap();
+            assert!(key.n() >= 1 << 62 && key.p() != key.q());
+            assert_eq!(DEFAULT_EXPONENT, key.e());
+            let lambda = lcm64(key.p() - 1, key.q() - 1).unwrap();
+            assert_eq!(1, key.e().mul_mod(key.d(), lambda));
+            [0, 1, 2, 65, key.n() / 3, key.n() - 1]
+                .into_iter()
+                .for_each(|m| {
+                    assert_eq!(Ok(m), key.decrypt(key.encrypt(m).unwrap()));
+                    assert!(key.verify(m, key.sign(m).unwrap()));
+                });
+        });
+    }
+
+    #[test]
+    fn generate_same_seed() {
+        assert_eq!(
+            RsaKeyPair::generate(&mut PrimeRng::new(3)),
+            RsaKeyPair::generate(&mut PrimeRng::new(3))
+        );
+    }
+
+    #[test]
+    fn to_json_textbook() {
+        assert_eq!(
+            r#"{"p":"61","q":"53","n":"3233","e":"17","d":"413","dp":"53","dq":"49","qinv":"38"}"#,
+            textbook().to_json()
+        );
+        assert_eq!(
+            r#"{"n":"3233","e":"17"}"#,
+            textbook().public_key().to_json()
+        );
+    }
+}
@@ -0,0 +1,388 @@
+use std::cmp::{max, min};
+use std::collections::VecDeque;
+
+use crate::iif;
+use crate::is_factor;
+use crate::modular::mod_inv64;
+use crate::prime_int::PrimeInt;
+use crate::util::sqrt;
+
+/// Amount of consecutive numbers sieved at once.
+const SEGMENT_SIZE: u64 = 1 << 18;
+
+/// Primes whose multiples are skipped by the wheel of the constellation sieve.
+pub(crate) const WHEEL_PRIMES: [u64; 6] = [2, 3, 5, 7, 11, 13];
+
+/// Product of the wheel primes.
+const WHEEL: u64 = 30030;
+
+/// Primes up to `limit` using a plain Sieve of Eratosthenes.
+pub(crate) fn small_primes(limit: u32) -> Vec<u32> {
+    let limit = limit as usize;
+    let mut composite = vec![false; limit + 1];
+    (2..=limit)
+        .filter(|&i| {
+            if !composite[i] {
+                (i * i..=limit).step_by(i).for_each(|j| composite[j] = true);
+            }
+            !composite[i]
+        })
+        .map(|i| i as u32)
+        .collect()
+}
+
+/// Number of primes up to `x` using Lucy_Hedgehog's algorithm, in `O(x^(3/4))` time and
+/// `O(sqrt(x))` memory.
+pub(crate) fn prime_count(x: u64) -> u64 {
+    if x < 2 {
+        return 0;
+    }
+    let root = x.isqrt() as usize;
+    // small[v] counts the survivors up to v, large[i] the survivors up to x / i.
+    let mut small: Vec<u64> = (0..=root as u64).map(|v| v.saturating_sub(1)).collect();
+    let mut large: Vec<u64> = (0..=root as u64)
+        .map(|i| iif!(i == 0 => 0; x / i - 1))
+        .collect();
+    for p in 2..=root {
+        if small[p] == small[p - 1] {
+            continue;
+        }
+        let count = small[p - 1];
+        let square = p * p;
+        for i in 1..=min(root, (x / square as u64) as usize) {
+            let d = i * p;
+            let survivors = iif!(d <= root => large[d]; small[(x / d as u64) as usize]);
+            large[i] -= survivors - count;
+        }
+        for v in (square..=root).rev() {
+            small[v] -= small[v / p] - count;
+        }
+    }
+    large[1]
+}
+
+/// Primes in `low..high`, crossing off multiples of `base`, which must hold every prime up to
+/// `sqrt(high - 1)`.
+fn segment(low: u64, high: u64, base: &[u32]) -> VecDeque<u32> {
+    let mut composite = vec![false; (high - low) as usize];
+    base.iter()
+        .map(|&p| p as u64)
+        .take_while(|p| p * p < high)
+        .for_each(|p| {
+            let start = max(p * p, low.div_ceil(p) * p);
+            (start..high)
+                .step_by(p as usize)
+                .for_each(|m| composite[(m - low) as usize] = true);
+        });
+    composite
+        .iter()
+        .zip(low..high)
+        .filter(|&(&c, n)| !c && n > 1)
+        .map(|(_, n)| n as u32)
+        .collect()
+}
+
+/// Every `n` in `begin..=last` such that `n + offset` is prime for each of the sorted `offsets`.
+/// Only the residues modulo the wheel that leave every member coprime to the wheel primes are
+/// sieved, each one as its own arithmetic progression, unless the range is too short to pay for
+/// setting them up. `begin` must be above the wheel primes and `last` plus the offsets must fit in a
+/// `u32`.
+pub(crate) fn constellations(begin: u32, last: u32, offsets: &[u32]) -> Vec<u32> {
+    if begin > last || offsets.is_empty() {
+        return Vec::new();
+    }
+    let (begin, last) = (begin as u64, last as u64);
+    let (low, top) = (offsets[0] as u64, last + offsets[offsets.len() - 1] as u64);
+    let base: Vec<(u64, u64)> = small_primes(sqrt(top as u32))
+        .into_iter()
+        .map(|q| q as u64)
+        .filter(|q| !WHEEL_PRIMES.contains(q))
+        .filter_map(|q| Some((q, mod_inv64(WHEEL, q)?)))
+        .collect();
+    let residues: Vec<u64> = (0..WHEEL)
+        .filter(|&r| {
+            WHEEL_PRIMES
+                .iter()
+                .all(|&q| offsets.iter().all(|&o| !is_factor!(r + o as u64, q)))
+        })
+        .collect();
+    if top - begin < (residues.len() * base.len() * offsets.len()) as u64 {
+        let primes: Vec<u32> = Sieve::new((begin + low) as u32, top as u32).collect();
+        return primes
+            .iter()
+            .map(|&p| p as u64 - low)
+            .take_while(|&n| n <= last)
+            .filter(|&n| {
+                offsets
+                    .iter()
+                    .all(|&o| primes.binary_search(&((n + o as u64) as u32)).is_ok())
+            })
+            .map(|n| n as u32)
+            .collect();
+    }
+    let mut found: Vec<u32> = residues
+        .into_iter()
+        .flat_map(|r| {
+            // The progression r + k * WHEEL for k in first..first + count.
+            let first = begin.saturating_sub(r).div_ceil(WHEEL);
+            let count = iif!(r + first * WHEEL <= last => (last - r) / WHEEL + 1 - first; 0);
+            let mut composite = vec![false; count as usize];
+            base.iter().for_each(|&(q, inverse)| {
+                offsets.iter().for_each(|&o| {
+                    let start = r + first * WHEEL + o as u64;
+                    let mut i = (q - start % q).mul_mod(inverse, q);
+                    let value = start + i * WHEEL;
+                    if value < q * q {
+                        i += (q * q - value).div_ceil(q * WHEEL) * q;
+                    }
+                    (i..count)
+                        .step_by(q as usize)
+                        .for_each(|i| composite[i as usize] = true);
+                });
+            });
+            composite
+                .into_iter()
+                .zip(first..)
+                .filter(|&(c, _)| !c)
+                .map(move |(_, k)| (r + k * WHEEL) as u32)
+        })
+        .collect();
+    found.sort_unstable();
+    found
+}
+
+/// Sophie Germain primes in `begin..=last`, i.e. the primes `q` with `2q + 1` prime too. Each
+/// segment crosses off at once the `q` that are composite and those for which `2q + 1` is, the
+/// latter being the `q = (p - 1) / 2 (mod p)` for an odd base prime `p`. `2 * last + 1` must fit in
+/// a `u32`.
+pub(crate) fn sophie_germain(begin: u32, last: u32) -> Vec<u32> {
+    let base = small_primes(sqrt(2 * last + 1));
+    let (mut low, last) = (begin as u64, last as u64);
+    let mut found = Vec::new();
+    while low <= last {
+        let high = min(low + SEGMENT_SIZE, last + 1);
+        let mut composite = vec![false; (high - low) as usize];
+        base.iter().map(|&p| p as u64).for_each(|p| {
+            let start = max(p * p, low.div_ceil(p) * p);
+            (start..high)
+                .step_by(p as usize)
+                .for_each(|q| composite[(q - low) as usize] = true);
+            if p > 2 {
+                // 2q + 1 = p itself is prime, crossing off starts from 2q + 1 = p^2.
+                let first = max((p * p - 1) / 2, low);
+                let start = first + ((p - 1) / 2 + p - first % p) % p;
+                (start..high)
+                    .step_by(p as usize)
+                    .for_each(|q| composite[(q - low) as usize] = true);
+            }
+        });
+        found.extend(
+            composite
+                .iter()
+                .zip(low..high)
+                .filter(|&(&c, q)| !c && q > 1)
+                .map(|(_, q)| q as u32),
+        );
+        low = high;
+    }
+    found
+}
+
+/// Segmented Sieve of Eratosthenes yielding the primes in a range from either end, using memory
+/// proportional to the segment size.
+#[derive(Clone)]
+pub(crate) struct Sieve {
+    base: Vec<u32>,
+    low: u64,
+    high: u64,
+    front: VecDeque<u32>,
+    back: VecDeque<u32>,
+}
+
+impl Sieve {
+    pub(crate) fn new(begin: u32, end: u32) -> Self {
+        Self {
+            base: small_primes(sqrt(end)),
+            low: begin as u64,
+            high: max(begin as u64, end as u64 + 1),
+            front: VecDeque::new(),
+            back: VecDeque::new(),
+        }
+    }
+}
+
+impl Iterator for Sieve {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        loop {
+            if let Some(prime) = self.front.pop_front() {
+                return Some(prime);
+            }
+            if self.low >= self.high {
+                return self.back.pop_front();
+            }
+            let high = min(self.low + SEGMENT_SIZE, self.high);
+            self.front = segment(self.low, high, &self.base);
+            self.low = high;
+        }
+    }
+}
+
+impl DoubleEndedIterator for Sieve {
+    fn next_back(&mut self) -> Option<u32> {
+        loop {
+            if let Some(prime) = self.back.pop_back() {
+                return Some(prime);
+            }
+            if self.low >= self.high {
+                return self.front.pop_back();
+            }
+            let low = max(self.low, self.high.saturating_sub(SEGMENT_SIZE));
+            self.back = segment(low, self.high, &self.base);
+            self.high = low;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_primes_0() {
+        let empty: Vec<u32> = Vec::new();
+        assert_eq!(empty, small_primes(0));
+    }
+
+    #[test]
+    fn small_primes_30() {
+        assert_eq!(vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29], small_primes(30));
+    }
+
+    #[test]
+    fn prime_count_small() {
+        (0..=100).for_each(|x| {
+            assert_eq!(small_primes(x).len() as u64, prime_count(x as u64), "{}", x);
+        });
+    }
+
+    #[test]
+    fn prime_count_1_000_000() {
+        assert_eq!(78498, prime_count(1_000_000));
+    }
+
+    #[test]
+    fn prime_count_u32_max() {
+        assert_eq!(203280221, prime_count(u32::MAX as u64));
+    }
+
+    #[test]
+    fn constellations_pairs() {
+        let primes = small_primes(100_002);
+        let pairs: Vec<u32> = Sieve::new(14, 100_000)
+            .filter(|&p| primes.binary_search(&(p + 2)).is_ok())
+            .collect();
+        assert_eq!(pairs, constellations(14, 100_000, &[0, 2]));
+    }
+
+    #[test]
+    fn constellations_single() {
+        assert_eq!(
+            Sieve::new(14, 1_000_000).collect::<Vec<u32>>(),
+            constellations(14, 1_000_000, &[0])
+        );
+    }
+
+    #[test]
+    fn constellations_top() {
+        assert_eq!(
+            vec![4294967279, 4294967291],
+            constellations(u32::MAX - 20, u32::MAX, &[0])
+        );
+    }
+
+    #[test]
+    fn constellations_empty_range() {
+        let empty: Vec<u32> = Vec::new();
+        assert_eq!(empty, constellations(100, 99, &[0, 2]));
+    }
+
+    #[test]
+    fn sophie_germain_0_100() {
+        assert_eq!(
+            vec![2, 3, 5, 11, 23, 29, 41, 53, 83, 89],
+            sophie_germain(0, 100)
+        );
+    }
+
+    #[test]
+    fn sophie_germain_1_000_000() {
+        assert_eq!(7746, sophie_germain(0, 1_000_000).len());
+    }
+
+    #[test]
+    fn sophie_germain_segments() {
+        let primes = small_primes(2_000_001);
+        let found: Vec<u32> = Sieve::new(500_000, 1_000_000)
+            .filter(|&q| primes.binary_search(&(2 * q + 1)).is_ok())
+            .collect();
+        assert_eq!(found, sophie_germain(500_000, 1_000_000));
+    }
+
+    #[test]
+    fn sophie_germain_top() {
+        assert_eq!(vec![2147483543], sophie_germain(2147483500, u32::MAX / 2));
+    }
+
+    #[test]
+    fn sieve_0_30() {
+        assert_eq!(
+            vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29],
+            Sieve::new(0, 30).collect::<Vec<u32>>()
+        );
+    }
+
+    #[test]
+    fn sieve_0_30_rev() {
+        assert_eq!(
+            vec![29, 23, 19, 17, 13, 11, 7, 5, 3, 2],
+            Sieve::new(0, 30).rev().collect::<Vec<u32>>()
+        );
+    }
+
+    #[test]
+    fn sieve_2_2() {
+        assert_eq!(vec![2], Sieve::new(2, 2).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn sieve_30_0() {
+        assert_eq!(None, Sieve::new(30, 0).next());
+    }
+
+    #[test]
+    fn sieve_max() {
+        assert_eq!(
+            vec![4294967279, 4294967291],
+            Sieve::new(u32::MAX - 20, u32::MAX).collect::<Vec<u32>>()
+        );
+    }
+
+    #[test]
+    fn sieve_count_segments() {
+        assert_eq!(78498, Sieve::new(0, 1_000_000).count());
+    }
+
+    #[test]
+    fn sieve_both_ends() {
+        let mut sieve = Sieve::new(0, 1_000_000);
+        let mut primes = Vec::new();
+        while let Some(prime) = sieve.next() {
+            primes.push(prime);
+            primes.extend(sieve.next_back());
+        }
+        primes.sort();
+        assert_eq!(Sieve::new(0, 1_000_000).collect::<Vec<u32>>(), primes);
+    }
+}
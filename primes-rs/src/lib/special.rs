@@ -0,0 +1,214 @@
+use wasm_bindgen::prelude::*;
+
+use crate::bigint::BigUint;
+use crate::error::PrimeError;
+use crate::iif;
+use crate::prime::PrimeNumber;
+use crate::prime_int::PrimeInt;
+
+/// Largest size in bits of the Mersenne and Fermat numbers tested, `2^16384 - 1` taking about 2
+/// seconds with the Lucas-Lehmer test and `F_14` as long with Pépin's. Each doubling costs eight
+/// times more.
+pub const MAX_BITS: u32 = 1 << 14;
+
+/// Largest `p_max` of [`mersenne_exponents_up_to`], which runs the Lucas-Lehmer test on every prime
+/// exponent up to it: about a quarter of a second all together, against minutes up to [`MAX_BITS`].
+pub const MAX_BATCH_EXPONENT: u32 = 1 << 11;
+
+/// Largest multiplier `k` of the divisors `2kp + 1` tried before running the Lucas-Lehmer test.
+const TRIAL_FACTOR_LIMIT: u64 = 1 << 12;
+
+/// Whether the Mersenne number `2^p - 1` is prime, with the Lucas-Lehmer test: `s = 4` squared
+/// minus two `p - 2` times must reach `0` modulo `2^p - 1`. Takes `O(p^3)` time, so `p` is capped
+/// at [`MAX_BITS`].
+#[wasm_bindgen(js_name = lucasLehmer)]
+pub fn lucas_lehmer(p: u32) -> Result<bool, PrimeError> {
+    Ok(match p {
+        p if p > MAX_BITS => return Err(PrimeError::Overflow),
+        2 => true,
+        p if !PrimeNumber::is_prime(p) => false,
+        p if has_small_factor(p) => false,
+        p => {
+            let (p, two) = (p as u64, BigUint::from_u64(2));
+            let modulus = BigUint::power_of_two(p).sub(&BigUint::from_u64(1));
+            let residue = (2..p).fold(BigUint::from_u64(4), |s, _| {
+                let square = mod_mersenne(&s.mul(&s), p, &modulus);
+                iif!(square >= two => square.sub(&two); square.add(&modulus).sub(&two))
+            });
+            residue.is_zero()
+        }
+    })
+}
+
+/// Whether the Fermat number `2^(2^n) + 1` is prime, with Pépin's test: `3^((F_n - 1) / 2)` must be
+/// `-1` modulo `F_n`, which takes `2^n - 1` squarings and `O(8^n)` time, so `2^n` is capped at
+/// [`MAX_BITS`].
+#[wasm_bindgen]
+pub fn pepin(n: u32) -> Result<bool, PrimeError> {
+    match n {
+        0 => return Ok(true),
+        n if n > MAX_BITS.ilog2() => return Err(PrimeError::Overflow),
+        _ => (),
+    }
+    let bits = 1 << n;
+    let modulus = BigUint::power_of_two(bits).add(&BigUint::from_u64(1));
+    let power = (1..bits).fold(BigUint::from_u64(3), |x, _| {
+        mod_fermat(&x.mul(&x), bits, &modulus)
+    });
+    Ok(power.add(&BigUint::from_u64(1)) == modulus)
+}
+
+/// Exponents `p <= p_max` of the Mersenne primes `2^p - 1`, `p_max` being at most
+/// [`MAX_BATCH_EXPONENT`].
+#[wasm_bindgen(js_name = mersenneExponentsUpTo)]
+pub fn mersenne_exponents_up_to(p_max: u32) -> Result<Vec<u32>, PrimeError> {
+    if p_max > MAX_BATCH_EXPONENT {
+        return Err(PrimeError::Overflow);
+    }
+    Ok(PrimeNumber::range_list(0, p_max)
+        .into_iter()
+        .filter(|&p| lucas_lehmer(p) == Ok(true))
+        .collect())
+}
+
+/// Whether `2^p - 1` has a divisor `q = 2kp + 1` for `k <= TRIAL_FACTOR_LIMIT`, only the `q = +-1
+/// (mod 8)` being candidates. Much cheaper than the Lucas-Lehmer test and finds most factors.
+fn has_small_factor(p: u32) -> bool {
+    (1..=TRIAL_FACTOR_LIMIT)
+        .map(|k| 2 * k * p as u64 + 1)
+        .filter(|q| q & 7 == 1 || q & 7 == 7)
+        .filter(|&q| 1u64.checked_shl(p).is_none_or(|power| q < power - 1))
+        .any(|q| 2u64.pow_mod(p as u64, q) == 1)
+}
+
+/// `num mod (2^p - 1)`, folding the bits above `p` back onto the low ones since `2^p = 1`.
+/// `modulus` is `2^p - 1`, built once by the caller.
+fn mod_mersenne(num: &BigUint, p: u64, modulus: &BigUint) -> BigUint {
+    let mut rest = num.clone();
+    while rest.bits() > p {
+        rest = rest.low_bits(p).add(&rest.shr(p));
+    }
+    iif!(rest == *modulus => BigUint::default(); rest)
+}
+
+/// `num mod (2^bits + 1)` for `num <= 2^(2 * bits)`, subtracting the bits above `bits` from the low
+/// ones since `2^bits = -1`. `modulus` is `2^bits + 1`, built once by the caller.
+fn mod_fermat(num: &BigUint, bits: u64, modulus: &BigUint) -> BigUint {
+    let (low, high) = (num.low_bits(bits), num.shr(bits));
+    iif!(low >= high => low.sub(&high); low.add(modulus).sub(&high))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lucas_lehmer_small() {
+        let found: Vec<u32> = (0..32).filter(|&p| lucas_lehmer(p) == Ok(true)).collect();
+        assert_eq!(vec![2, 3, 5, 7, 13, 17, 19, 31], found);
+    }
+
+    #[test]
+    fn lucas_lehmer_11() {
+        assert_eq!(Ok(false), lucas_lehmer(11));
+    }
+
+    #[test]
+    fn lucas_lehmer_127() {
+        assert_eq!(Ok(true), lucas_lehmer(127));
+    }
+
+    #[test]
+    fn lucas_lehmer_2203() {
+        assert_eq!(Ok(true), lucas_lehmer(2203));
+    }
+
+    #[test]
+    fn lucas_lehmer_2207() {
+        assert_eq!(Ok(false), lucas_lehmer(2207));
+    }
+
+    #[test]
+    fn pepin_known() {
+        let found: Vec<bool> = (0..=10).map(|n| pepin(n).unwrap()).collect();
+        assert_eq!(
+            vec![
+                true, true, true, true, true, false, false, false, false, false, false
+            ],
+            found
+        );
+    }
+
+    #[test]
+    fn mersenne_exponents_up_to_1279() {
+        assert_eq!(
+            Ok(vec![
+                2, 3, 5, 7, 13, 17, 19, 31, 61, 89, 107, 127, 521, 607, 1279
+            ]),
+            mersenne_exponents_up_to(1279)
+        );
+    }
+
+    #[test]
+    fn mersenne_exponents_up_to_1() {
+        assert_eq!(Ok(Vec::new()), mersenne_exponents_up_to(1));
+    }
+
+    #[test]
+    fn lucas_lehmer_above_max_bits() {
+        assert_eq!(Err(PrimeError::Overflow), lucas_lehmer(MAX_BITS + 1));
+        assert_eq!(Err(PrimeError::Overflow), lucas_lehmer(u32::MAX));
+    }
+
+    #[test]
+    fn mersenne_exponents_up_to_above_max_batch_exponent() {
+        assert_eq!(
+            Err(PrimeError::Overflow),
+            mersenne_exponents_up_to(MAX_BATCH_EXPONENT + 1)
+        );
+        assert_eq!(
+            Err(PrimeError::Overflow),
+            mersenne_exponents_up_to(MAX_BITS)
+        );
+    }
+
+    #[test]
+    fn pepin_above_max_bits() {
+        [15, 25, 63, 64, u32::MAX].into_iter().for_each(|n| {
+            assert_eq!(Err(PrimeError::Overflow), pepin(n));
+        });
+    }
+
+    #[test]
+    fn has_small_factor_small() {
+        assert!(has_small_factor(11));
+        assert!(has_small_factor(2351));
+        assert!(
+            ![3, 5, 7, 13, 17, 19, 31, 61]
+                .into_iter()
+                .any(has_small_factor)
+        );
+    }
+
+    #[test]
+    fn mod_mersenne_matches_u64() {
+        [0u64, 1, 30, 31, 32, 1000, u64::MAX]
+            .into_iter()
+            .for_each(|num| {
+                assert_eq!(
+                    BigUint::from_u64(num % 31),
+                    mod_mersenne(&BigUint::from_u64(num), 5, &BigUint::from_u64(31))
+                );
+            });
+    }
+
+    #[test]
+    fn mod_fermat_matches_u64() {
+        (0..=256u64 * 256).step_by(97).for_each(|num| {
+            assert_eq!(
+                BigUint::from_u64(num % 257),
+                mod_fermat(&BigUint::from_u64(num), 8, &BigUint::from_u64(257))
+            );
+        });
+    }
+}
@@ -18,6 +18,7 @@ pub fn sqrt(num: u32) -> u32 {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
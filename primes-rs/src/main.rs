@@ -1,9 +1,12 @@
 use primes::factor::{factor_list, factor_list_in};
 use primes::prime::PrimeNumber;
+use primes::prime64::PrimeNumber64;
 use primes::util::is_even;
 use primes::util::is_odd;
 
+#[allow(clippy::legacy_numeric_constants)]
 const U32_MAX: u32 = std::u32::MAX;
+const U64_MAX: u64 = u64::MAX;
 
 fn main() {
     (0..15).chain(U32_MAX - 4..=U32_MAX).for_each(|i| {
@@ -36,6 +39,9 @@ fn main() {
         process_candidates(3, &[1]);
     }
     println!("{}th prime: {:?}", 9, PrimeNumber::nth(8));
+    if let Err(error) = PrimeNumber::try_nth(U32_MAX) {
+        println!("{}th prime: {} ({})", U32_MAX, error, error.code());
+    }
     println!("random prime: {:?}", PrimeNumber::random());
     {
         let overflow = |value: u32, asc: bool, desc: &str| {
@@ -75,6 +81,16 @@ fn main() {
         println!("{}", random_range(c, b));
         println!("{}", random_range(c, c));
     }
+    (U64_MAX - 64..=U64_MAX).for_each(|i| {
+        if let Some(prime) = PrimeNumber64::prime(i) {
+            println!(
+                "{} is prime, factors of {}: {:?}",
+                prime,
+                i + 1,
+                PrimeNumber64::prime_factor_list(i + 1)
+            );
+        }
+    });
 }
 
 #[cfg(test)]